@@ -16,6 +16,31 @@ macro_rules! Token {
     [--] => {$crate::Decrement};
     [=] => {$crate::Assign};
     [==] => {$crate::Equals};
+    [!=] => {$crate::NotEquals};
+    [*] => {$crate::Mul};
+    [*=] => {$crate::MulAssign};
+    [/] => {$crate::Div};
+    [/=] => {$crate::DivAssign};
+    [%] => {$crate::Rem};
+    [%=] => {$crate::RemAssign};
+    [<] => {$crate::Less};
+    [<=] => {$crate::LessEquals};
+    [>] => {$crate::Greater};
+    [>=] => {$crate::GreaterEquals};
+    [!] => {$crate::Not};
+    [&&] => {$crate::And};
+    [||] => {$crate::Or};
+    [&] => {$crate::BitAnd};
+    [&=] => {$crate::BitAndAssign};
+    [|] => {$crate::BitOr};
+    [|=] => {$crate::BitOrAssign};
+    [^] => {$crate::BitXor};
+    [^=] => {$crate::BitXorAssign};
+    [~] => {$crate::BitNot};
+    [<<] => {$crate::ShiftLeft};
+    [<<=] => {$crate::ShiftLeftAssign};
+    [>>] => {$crate::ShiftRight};
+    [>>=] => {$crate::ShiftRightAssign};
 }
 
 macro_rules! make_node {
@@ -40,6 +65,7 @@ make_node! {RBrace}
 make_node! {LBracket}
 make_node! {RBracket}
 make_node! {Comma}
+make_node! {Hash}
 make_node! {End}
 
 make_node! {Plus}
@@ -50,12 +76,122 @@ make_node! {SubAssign}
 make_node! {Decrement}
 make_node! {Assign}
 make_node! {Equals}
+make_node! {NotEquals}
+make_node! {Mul}
+make_node! {MulAssign}
+make_node! {Div}
+make_node! {DivAssign}
+make_node! {Rem}
+make_node! {RemAssign}
+make_node! {Less}
+make_node! {LessEquals}
+make_node! {Greater}
+make_node! {GreaterEquals}
+make_node! {Not}
+make_node! {And}
+make_node! {Or}
+make_node! {BitAnd}
+make_node! {BitAndAssign}
+make_node! {BitOr}
+make_node! {BitOrAssign}
+make_node! {BitXor}
+make_node! {BitXorAssign}
+make_node! {BitNot}
+make_node! {ShiftLeft}
+make_node! {ShiftLeftAssign}
+make_node! {ShiftRight}
+make_node! {ShiftRightAssign}
 
 make_node! {If}
 make_node! {Else}
 make_node! {While}
 make_node! {For}
 make_node! {Return}
+make_node! {True}
+make_node! {False}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Equals,
+    NotEquals,
+    Less,
+    LessEquals,
+    Greater,
+    GreaterEquals,
+    And,
+    Or,
+    BitAnd,
+    BitOr,
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
+}
+
+impl BinaryOp {
+    /// Whether this operator compares its operands rather than combining them.
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            Self::Equals
+                | Self::NotEquals
+                | Self::Less
+                | Self::LessEquals
+                | Self::Greater
+                | Self::GreaterEquals
+        )
+    }
+
+    pub fn is_logical(&self) -> bool {
+        matches!(self, Self::And | Self::Or)
+    }
+}
+
+impl std::fmt::Display for BinaryOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Add => "+",
+            Self::Sub => "-",
+            Self::Mul => "*",
+            Self::Div => "/",
+            Self::Rem => "%",
+            Self::Equals => "==",
+            Self::NotEquals => "!=",
+            Self::Less => "<",
+            Self::LessEquals => "<=",
+            Self::Greater => ">",
+            Self::GreaterEquals => ">=",
+            Self::And => "&&",
+            Self::Or => "||",
+            Self::BitAnd => "&",
+            Self::BitOr => "|",
+            Self::BitXor => "^",
+            Self::ShiftLeft => "<<",
+            Self::ShiftRight => ">>",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Neg,
+    Not,
+    BitNot,
+}
+
+impl std::fmt::Display for UnaryOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Neg => "-",
+            Self::Not => "!",
+            Self::BitNot => "~",
+        })
+    }
+}
 
 #[rowdy_macros::ast(typed)]
 pub mod base {
@@ -73,6 +209,7 @@ pub mod base {
     #[derive(Debug)]
     pub struct Function {
         pub span: Span,
+        pub attributes: Vec<Attribute>,
         pub return_type: Type,
         pub name: Symbol,
         pub parameters: Vec<Declaration>,
@@ -85,6 +222,20 @@ pub mod base {
         }
     }
 
+    /// An attribute like `#[allow(unused_variable)]`, written before a function.
+    #[derive(Debug)]
+    pub struct Attribute {
+        pub span: Span,
+        pub name: Symbol,
+        pub arguments: Vec<Symbol>,
+    }
+
+    impl Spanned for Attribute {
+        fn span(&self) -> Span {
+            self.span
+        }
+    }
+
     #[ast::typed{pub typed: ::rowdy_types::TypeID}]
     #[derive(Debug)]
     pub struct Declaration {
@@ -116,26 +267,128 @@ pub mod base {
     pub enum Statement {
         Declaration(Declaration, Option<Expression>),
         Assignment(Symbol, Expression),
-        FunctionCall(Symbol, Vec<Expression>),
+        /// An assignment like `x += 1`, stored as the assignment of `x + 1` to `x`.
+        CompoundAssignment(Symbol, Expression),
+        FunctionCall(FunctionCall),
+        /// A block on its own, which gives the variables declared in it their own scope.
+        Block(BracedExpression),
+        If(IfStatement),
+        While(WhileLoop),
+        For(ForLoop),
+        Return(ReturnStatement),
+        /// A statement which failed to parse, covering the tokens skipped while recovering.
+        #[ast::base]
+        Error(Span),
+    }
+
+    #[ast::base]
+    impl Spanned for Statement {
+        fn span(&self) -> Span {
+            match self {
+                Statement::Declaration(decl, Some(expr)) => decl.span.combine(expr.span()),
+                Statement::Declaration(decl, None) => decl.span,
+                Statement::Assignment(symbol, expr)
+                | Statement::CompoundAssignment(symbol, expr) => symbol.span.combine(expr.span()),
+                Statement::FunctionCall(call) => call.span,
+                Statement::Block(block) => block.span,
+                Statement::If(if_statement) => if_statement.span,
+                Statement::While(while_loop) => while_loop.span,
+                Statement::For(for_loop) => for_loop.span,
+                Statement::Return(return_statement) => return_statement.span,
+                Statement::Error(span) => *span,
+            }
+        }
     }
 
+    #[ast::typed]
     impl Spanned for Statement {
         fn span(&self) -> Span {
             match self {
                 Statement::Declaration(decl, Some(expr)) => decl.span.combine(expr.span()),
                 Statement::Declaration(decl, None) => decl.span,
-                Statement::Assignment(symbol, expr) => symbol.span.combine(expr.span()),
-                Statement::FunctionCall(symbol, exprs) => {
-                    let mut span = symbol.span;
-                    if let Some(last) = exprs.last() {
-                        span = span.combine(last.span())
-                    }
-                    span
-                }
+                Statement::Assignment(symbol, expr)
+                | Statement::CompoundAssignment(symbol, expr) => symbol.span.combine(expr.span()),
+                Statement::FunctionCall(call) => call.span,
+                Statement::Block(block) => block.span,
+                Statement::If(if_statement) => if_statement.span,
+                Statement::While(while_loop) => while_loop.span,
+                Statement::For(for_loop) => for_loop.span,
+                Statement::Return(return_statement) => return_statement.span,
             }
         }
     }
 
+    #[derive(Debug)]
+    pub struct IfStatement {
+        pub span: Span,
+        pub condition: Expression,
+        pub body: BracedExpression,
+        pub else_branch: Option<ElseBranch>,
+    }
+
+    impl Spanned for IfStatement {
+        fn span(&self) -> Span {
+            self.span
+        }
+    }
+
+    #[derive(Debug)]
+    pub enum ElseBranch {
+        ElseIf(Box<IfStatement>),
+        Else(BracedExpression),
+    }
+
+    impl Spanned for ElseBranch {
+        fn span(&self) -> Span {
+            match self {
+                ElseBranch::ElseIf(if_statement) => if_statement.span,
+                ElseBranch::Else(body) => body.span,
+            }
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct WhileLoop {
+        pub span: Span,
+        pub condition: Expression,
+        pub body: BracedExpression,
+    }
+
+    impl Spanned for WhileLoop {
+        fn span(&self) -> Span {
+            self.span
+        }
+    }
+
+    /// A C-style `for (init; condition; step) { ... }` loop.
+    /// Any of the three header parts may be left out.
+    #[derive(Debug)]
+    pub struct ForLoop {
+        pub span: Span,
+        pub init: Option<Box<Statement>>,
+        pub condition: Option<Expression>,
+        pub step: Option<Box<Statement>>,
+        pub body: BracedExpression,
+    }
+
+    impl Spanned for ForLoop {
+        fn span(&self) -> Span {
+            self.span
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct ReturnStatement {
+        pub span: Span,
+        pub value: Option<Expression>,
+    }
+
+    impl Spanned for ReturnStatement {
+        fn span(&self) -> Span {
+            self.span
+        }
+    }
+
     #[ast::typed{pub typed: ::rowdy_types::TypeID}]
     #[derive(Debug)]
     pub struct IntLit {
@@ -162,13 +415,101 @@ pub mod base {
         }
     }
 
+    #[ast::typed{pub typed: ::rowdy_types::TypeID}]
+    #[derive(Debug)]
+    pub struct BoolLit {
+        pub span: Span,
+        pub value: bool,
+    }
+
+    impl Spanned for BoolLit {
+        fn span(&self) -> Span {
+            self.span
+        }
+    }
+
+    #[ast::typed{pub typed: ::rowdy_types::TypeID}]
+    #[derive(Debug)]
+    pub struct StringLit {
+        pub span: Span,
+        pub value: String,
+    }
+
+    impl Spanned for StringLit {
+        fn span(&self) -> Span {
+            self.span
+        }
+    }
+
+    #[ast::typed{pub typed: ::rowdy_types::TypeID}]
+    #[derive(Debug)]
+    pub struct CharLit {
+        pub span: Span,
+        pub value: char,
+    }
+
+    impl Spanned for CharLit {
+        fn span(&self) -> Span {
+            self.span
+        }
+    }
+
+    #[ast::typed{pub typed: ::rowdy_types::TypeID}]
+    #[derive(Debug)]
+    pub struct FunctionCall {
+        pub span: Span,
+        pub name: Symbol,
+        pub arguments: Vec<Expression>,
+    }
+
+    impl Spanned for FunctionCall {
+        fn span(&self) -> Span {
+            self.span
+        }
+    }
+
+    #[ast::typed{pub typed: ::rowdy_types::TypeID}]
+    #[derive(Debug)]
+    pub struct BinaryExpression {
+        pub span: Span,
+        pub op: BinaryOp,
+        pub lhs: Box<Expression>,
+        pub rhs: Box<Expression>,
+    }
+
+    impl Spanned for BinaryExpression {
+        fn span(&self) -> Span {
+            self.span
+        }
+    }
+
+    #[ast::typed{pub typed: ::rowdy_types::TypeID}]
+    #[derive(Debug)]
+    pub struct UnaryExpression {
+        pub span: Span,
+        pub op: UnaryOp,
+        pub operand: Box<Expression>,
+    }
+
+    impl Spanned for UnaryExpression {
+        fn span(&self) -> Span {
+            self.span
+        }
+    }
+
     #[ast::typed{pub typed: ::rowdy_types::TypeID}]
     #[derive(Debug)]
     pub enum Expression {
         Braced(BracedExpression),
         IntLit(IntLit),
         FloatLit(FloatLit),
+        BoolLit(BoolLit),
+        StringLit(StringLit),
+        CharLit(CharLit),
         Symbol(Symbol),
+        FunctionCall(FunctionCall),
+        Binary(BinaryExpression),
+        Unary(UnaryExpression),
     }
 
     #[ast::base]
@@ -178,7 +519,13 @@ pub mod base {
                 Expression::Braced(braced) => braced.span,
                 Expression::IntLit(int_lit) => int_lit.span,
                 Expression::FloatLit(float_lit) => float_lit.span,
+                Expression::BoolLit(bool_lit) => bool_lit.span,
+                Expression::StringLit(string_lit) => string_lit.span,
+                Expression::CharLit(char_lit) => char_lit.span,
                 Expression::Symbol(symbol) => symbol.span,
+                Expression::FunctionCall(call) => call.span,
+                Expression::Binary(binary) => binary.span,
+                Expression::Unary(unary) => unary.span,
             }
         }
     }
@@ -190,7 +537,13 @@ pub mod base {
                 ExpressionInner::Braced(braced) => braced.span,
                 ExpressionInner::IntLit(int_lit) => int_lit.span,
                 ExpressionInner::FloatLit(float_lit) => float_lit.span,
+                ExpressionInner::BoolLit(bool_lit) => bool_lit.span,
+                ExpressionInner::StringLit(string_lit) => string_lit.span,
+                ExpressionInner::CharLit(char_lit) => char_lit.span,
                 ExpressionInner::Symbol(symbol) => symbol.span,
+                ExpressionInner::FunctionCall(call) => call.span,
+                ExpressionInner::Binary(binary) => binary.span,
+                ExpressionInner::Unary(unary) => unary.span,
             }
         }
     }
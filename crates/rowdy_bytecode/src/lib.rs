@@ -3,16 +3,85 @@ use rowdy_macros::Bytes;
 #[derive(Debug, Bytes)]
 pub enum Instruction {
     Push(i32),
+    /// Push a reference to the constant with the given index in the constant pool.
+    Const(u32),
     Pop,
+    /// Push the value of a local variable slot of the current frame.
+    Load(u32),
+    /// Pop a value into a local variable slot of the current frame.
+    Store(u32),
     Add,
     Sub,
     Mul,
     Div,
+    Rem,
+    Neg,
+    Not,
+    BitNot,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
+    // Unsigned integers are stored zero extended and need their own division, comparisons and shift.
+    DivU,
+    RemU,
+    LtU,
+    LeU,
+    GtU,
+    GeU,
+    ShrU,
+    /// Truncate the value on top of the stack to the given number of bits and sign extend it,
+    /// wrapping it around into the range of a signed integer that wide.
+    WrapSigned(u32),
+    /// Truncate the value on top of the stack to the given number of bits and zero extend it,
+    /// wrapping it around into the range of an unsigned integer that wide.
+    WrapUnsigned(u32),
+    // Floats live on the stack as their bit pattern and have their own arithmetic and comparisons.
+    FAdd,
+    FSub,
+    FMul,
+    FDiv,
+    FRem,
+    FNeg,
+    FEq,
+    FNe,
+    FLt,
+    FLe,
+    FGt,
+    FGe,
+    /// Continue execution at the given byte offset.
+    Jump(u32),
+    /// Pop a value and jump to the given byte offset if it is zero.
+    JumpIfFalse(u32),
+    /// Call the function with the given index in the function table.
+    /// Its arguments are popped off the stack into the first slots of the new frame.
+    Call(u32),
+    /// Pop the return value, leave the current function
+    /// and push the return value for the caller.
+    Ret,
+}
+
+#[derive(Debug, Clone)]
+pub struct FunctionEntry {
+    pub name: String,
+    /// Byte offset of the function's first instruction.
+    pub address: u32,
+    pub parameter_count: u32,
 }
 
 #[derive(Debug, Default)]
 pub struct Bytecode {
     vec: Vec<u8>,
+    functions: Vec<FunctionEntry>,
+    /// Constant pool holding the data of string literals.
+    constants: Vec<String>,
 }
 
 impl Bytecode {
@@ -26,12 +95,70 @@ impl Bytecode {
         self
     }
 
+    /// Adds a function to the function table and returns its index for use with `Call`.
+    pub fn push_function(&mut self, function: FunctionEntry) -> u32 {
+        self.functions.push(function);
+        self.functions.len() as u32 - 1
+    }
+
+    pub fn function(&self, index: u32) -> Option<&FunctionEntry> {
+        self.functions.get(index as usize)
+    }
+
+    pub fn function_by_name(&self, name: &str) -> Option<&FunctionEntry> {
+        self.functions.iter().find(|function| function.name == name)
+    }
+
+    /// Adds a string to the constant pool and returns its index for use with `Const`.
+    /// Identical strings share a single entry.
+    pub fn push_constant(&mut self, value: &str) -> u32 {
+        match self.constants.iter().position(|constant| constant == value) {
+            Some(index) => index as u32,
+            None => {
+                self.constants.push(value.to_owned());
+                self.constants.len() as u32 - 1
+            }
+        }
+    }
+
+    pub fn constant(&self, index: u32) -> Option<&str> {
+        self.constants.get(index as usize).map(String::as_str)
+    }
+
+    /// Overwrites the instruction at byte offset `index`.
+    /// Used to fill in jump targets once they are known, so the new instruction
+    /// must be the same size as the one it replaces.
+    pub fn patch(&mut self, index: usize, instruction: Instruction) -> &mut Self {
+        let (bytes, count) = instruction.as_bytes();
+        let (_, old_count) = Instruction::from_bytes(&self.vec[index..]);
+        assert_eq!(
+            count, old_count,
+            "Patched instruction must be the same size as the original"
+        );
+        self.vec[index..index + count].copy_from_slice(&bytes[0..count]);
+        self
+    }
+
+    /// Decodes the instruction starting at byte offset `index`.
+    /// Returns the instruction along with its size in bytes.
+    pub fn get(&self, index: usize) -> Option<(Instruction, usize)> {
+        if index < self.len() {
+            Some(Instruction::from_bytes(&self.vec[index..]))
+        } else {
+            None
+        }
+    }
+
     /// Returns the length of the bytecode in bytes, *not* instructions.
     pub fn len(&self) -> usize {
         self.vec.len()
     }
 
-    pub fn iter(&self) -> BytecodeIter {
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    pub fn iter(&self) -> BytecodeIter<'_> {
         BytecodeIter::new(self)
     }
 }
@@ -61,12 +188,8 @@ impl<'a> Iterator for BytecodeIter<'a> {
     type Item = Instruction;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.index < self.bytecode.len() {
-            let (instruction, size) = Instruction::from_bytes(&self.bytecode.vec[self.index..]);
-            self.index += size;
-            Some(instruction)
-        } else {
-            None
-        }
+        let (instruction, size) = self.bytecode.get(self.index)?;
+        self.index += size;
+        Some(instruction)
     }
 }
@@ -1,5 +1,7 @@
 use rowdy_ast::typed::*;
-use rowdy_bytecode::{Bytecode, Instruction};
+use rowdy_bytecode::{Bytecode, FunctionEntry, Instruction};
+use rowdy_types::{Primitive, TypeID};
+use std::collections::BTreeMap;
 
 pub fn generate_bytecode(ast: &Ast) -> Bytecode {
     let mut gen = Generator::default();
@@ -10,18 +12,113 @@ pub fn generate_bytecode(ast: &Ast) -> Bytecode {
 #[derive(Debug, Default)]
 struct Generator {
     bytecode: Bytecode,
+    /// The variables in scope, innermost scope last.
+    scopes: Vec<Scope>,
+    next_slot: u32,
+    /// Index of each function in the function table.
+    functions: BTreeMap<String, u32>,
+}
+
+#[derive(Debug)]
+struct Scope {
+    /// The first frame slot used by this scope.
+    /// Everything from here up is free again once the scope ends.
+    first_slot: u32,
+    slots: BTreeMap<String, u32>,
+}
+
+impl Generator {
+    fn push_scope(&mut self) {
+        self.scopes.push(Scope {
+            first_slot: self.next_slot,
+            slots: BTreeMap::new(),
+        });
+    }
+
+    /// Leaves the innermost scope, making its slots available to later declarations.
+    fn pop_scope(&mut self) {
+        let scope = self
+            .scopes
+            .pop()
+            .expect("Popped a scope without pushing one first");
+        self.next_slot = scope.first_slot;
+    }
+
+    /// Gives a newly declared variable the next free slot in the current frame.
+    fn declare(&mut self, name: &str) -> u32 {
+        let slot = self.next_slot;
+        self.next_slot += 1;
+        self.scopes
+            .last_mut()
+            .expect("Declared a variable outside of any scope")
+            .slots
+            .insert(name.to_owned(), slot);
+        slot
+    }
+
+    fn slot(&self, name: &str) -> u32 {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.slots.get(name))
+            .copied()
+            .expect("Variable wasn't declared. This should have been caught by the type checker")
+    }
+
+    /// The byte offset of the next instruction to be emitted.
+    fn address(&self) -> u32 {
+        self.bytecode.len() as u32
+    }
+
+    /// Emits a jump whose target isn't known yet and returns where it was emitted.
+    /// The target gets filled in with `patch_jump`.
+    fn placeholder_jump(&mut self) -> usize {
+        let index = self.bytecode.len();
+        self.bytecode.push(Instruction::Jump(u32::MAX));
+        index
+    }
+
+    /// Points the placeholder jump at `index` to the current address.
+    fn patch_jump(&mut self, index: usize, jump: fn(u32) -> Instruction) {
+        let target = self.address();
+        self.bytecode.patch(index, jump(target));
+    }
+
+    /// Wraps the integer on top of the stack back into the range of `typ`,
+    /// after an operation which could have taken it out of that range.
+    /// Does nothing for types which aren't narrower than the stack's 64 bits.
+    fn wrap(&mut self, typ: TypeID) {
+        let Some(primitive) = Primitive::from_id(typ) else {
+            return;
+        };
+        match primitive.bits() {
+            Some(bits) if bits < 64 && primitive.is_signed() => {
+                self.bytecode.push(Instruction::WrapSigned(bits));
+            }
+            Some(bits) if bits < 64 => {
+                self.bytecode.push(Instruction::WrapUnsigned(bits));
+            }
+            _ => {}
+        }
+    }
 }
 
 impl Visit<Ast> for Generator {
     type Output = ();
 
     fn visit(&mut self, node: &Ast) -> Self::Output {
-        // NOTE: Only can handle a single function right now
-        self.visit(
-            node.functions
-                .get(0)
-                .expect("There was no function to compile to bytecode"),
-        );
+        // Functions are added to the function table in program order,
+        // so their indices are known before any of them are compiled.
+        self.functions = node
+            .functions
+            .iter()
+            .enumerate()
+            .map(|(index, function)| (function.name.text.clone(), index as u32))
+            .collect();
+
+        for function in &node.functions {
+            self.visit(function);
+        }
     }
 }
 
@@ -29,7 +126,22 @@ impl Visit<Function> for Generator {
     type Output = ();
 
     fn visit(&mut self, node: &Function) -> Self::Output {
+        self.bytecode.push_function(FunctionEntry {
+            name: node.name.text.clone(),
+            address: self.address(),
+            parameter_count: node.parameters.len() as u32,
+        });
+
+        self.next_slot = 0;
+        self.push_scope();
+        for param in &node.parameters {
+            self.declare(&param.name.text);
+        }
         self.visit(&node.expr);
+        self.pop_scope();
+        // Falling off the end of a function returns 0.
+        self.bytecode.push(Instruction::Push(0));
+        self.bytecode.push(Instruction::Ret);
     }
 }
 
@@ -37,9 +149,11 @@ impl Visit<BracedExpression> for Generator {
     type Output = ();
 
     fn visit(&mut self, node: &BracedExpression) -> Self::Output {
+        self.push_scope();
         for stmt in &node.statements {
             self.visit(stmt);
         }
+        self.pop_scope();
     }
 }
 
@@ -48,11 +162,108 @@ impl Visit<Statement> for Generator {
 
     fn visit(&mut self, node: &Statement) -> Self::Output {
         match node {
-            Statement::Declaration(_, Some(expr)) => self.visit(expr),
-            Statement::Declaration(_, None) => todo!(),
-            Statement::Assignment(_, _) => todo!(),
-            Statement::FunctionCall(_, _) => todo!(),
+            Statement::Declaration(declaration, Some(expr)) => {
+                self.visit(expr);
+                let slot = self.declare(&declaration.name.text);
+                self.bytecode.push(Instruction::Store(slot));
+            }
+            Statement::Declaration(declaration, None) => {
+                self.bytecode.push(Instruction::Push(0));
+                let slot = self.declare(&declaration.name.text);
+                self.bytecode.push(Instruction::Store(slot));
+            }
+            Statement::Assignment(target, expr) | Statement::CompoundAssignment(target, expr) => {
+                self.visit(expr);
+                let slot = self.slot(&target.text);
+                self.bytecode.push(Instruction::Store(slot));
+            }
+            Statement::FunctionCall(call) => {
+                self.visit(call);
+                // The return value isn't used.
+                self.bytecode.push(Instruction::Pop);
+            }
+            Statement::Block(block) => self.visit(block),
+            Statement::If(if_statement) => self.visit(if_statement),
+            Statement::While(while_loop) => self.visit(while_loop),
+            Statement::For(for_loop) => self.visit(for_loop),
+            Statement::Return(return_statement) => self.visit(return_statement),
+        }
+    }
+}
+
+impl Visit<ReturnStatement> for Generator {
+    type Output = ();
+
+    fn visit(&mut self, node: &ReturnStatement) -> Self::Output {
+        match &node.value {
+            Some(value) => self.visit(value),
+            None => {
+                self.bytecode.push(Instruction::Push(0));
+            }
+        }
+        self.bytecode.push(Instruction::Ret);
+    }
+}
+
+impl Visit<IfStatement> for Generator {
+    type Output = ();
+
+    fn visit(&mut self, node: &IfStatement) -> Self::Output {
+        self.visit(&node.condition);
+        let skip_body = self.placeholder_jump();
+        self.visit(&node.body);
+
+        match &node.else_branch {
+            Some(else_branch) => {
+                let skip_else = self.placeholder_jump();
+                self.patch_jump(skip_body, Instruction::JumpIfFalse);
+                match else_branch {
+                    ElseBranch::ElseIf(if_statement) => self.visit(if_statement.as_ref()),
+                    ElseBranch::Else(body) => self.visit(body),
+                }
+                self.patch_jump(skip_else, Instruction::Jump);
+            }
+            None => self.patch_jump(skip_body, Instruction::JumpIfFalse),
+        }
+    }
+}
+
+impl Visit<WhileLoop> for Generator {
+    type Output = ();
+
+    fn visit(&mut self, node: &WhileLoop) -> Self::Output {
+        let start = self.address();
+        self.visit(&node.condition);
+        let exit = self.placeholder_jump();
+        self.visit(&node.body);
+        self.bytecode.push(Instruction::Jump(start));
+        self.patch_jump(exit, Instruction::JumpIfFalse);
+    }
+}
+
+impl Visit<ForLoop> for Generator {
+    type Output = ();
+
+    fn visit(&mut self, node: &ForLoop) -> Self::Output {
+        // Variables declared in the header are only visible inside the loop.
+        self.push_scope();
+        if let Some(init) = &node.init {
+            self.visit(init.as_ref());
+        }
+        let start = self.address();
+        let exit = node.condition.as_ref().map(|condition| {
+            self.visit(condition);
+            self.placeholder_jump()
+        });
+        self.visit(&node.body);
+        if let Some(step) = &node.step {
+            self.visit(step.as_ref());
         }
+        self.bytecode.push(Instruction::Jump(start));
+        if let Some(exit) = exit {
+            self.patch_jump(exit, Instruction::JumpIfFalse);
+        }
+        self.pop_scope();
     }
 }
 
@@ -61,14 +272,162 @@ impl Visit<Expression> for Generator {
 
     fn visit(&mut self, node: &Expression) -> Self::Output {
         match &node.inner {
-            typed::ExpressionInner::Braced(_) => todo!(),
-            typed::ExpressionInner::IntLit(lit) => self.bytecode.push(Instruction::Push(lit.value)),
-            typed::ExpressionInner::FloatLit(_) => todo!(),
-            typed::ExpressionInner::Symbol(_) => todo!(),
+            typed::ExpressionInner::Braced(braced) => {
+                self.visit(braced);
+                // Blocks don't produce a value yet, so they all evaluate to `void`.
+                self.bytecode.push(Instruction::Push(0));
+            }
+            typed::ExpressionInner::IntLit(lit) => {
+                self.bytecode.push(Instruction::Push(lit.value));
+            }
+            typed::ExpressionInner::FloatLit(lit) => {
+                self.bytecode
+                    .push(Instruction::Push(lit.value.to_bits() as i32));
+            }
+            typed::ExpressionInner::BoolLit(lit) => {
+                self.bytecode.push(Instruction::Push(lit.value as i32));
+            }
+            typed::ExpressionInner::StringLit(lit) => {
+                let index = self.bytecode.push_constant(&lit.value);
+                self.bytecode.push(Instruction::Const(index));
+            }
+            typed::ExpressionInner::CharLit(lit) => {
+                self.bytecode.push(Instruction::Push(lit.value as i32));
+            }
+            typed::ExpressionInner::Symbol(symbol) => {
+                let slot = self.slot(&symbol.text);
+                self.bytecode.push(Instruction::Load(slot));
+            }
+            typed::ExpressionInner::FunctionCall(call) => self.visit(call),
+            typed::ExpressionInner::Binary(binary) => self.visit(binary),
+            typed::ExpressionInner::Unary(unary) => self.visit(unary),
         };
     }
 }
 
+impl Visit<FunctionCall> for Generator {
+    type Output = ();
+
+    fn visit(&mut self, node: &FunctionCall) -> Self::Output {
+        for argument in &node.arguments {
+            self.visit(argument);
+        }
+        if let Some(&index) = self.functions.get(&node.name.text) {
+            self.bytecode.push(Instruction::Call(index));
+            return;
+        }
+        // Calling an integer type converts its argument to that type.
+        let target = Primitive::from_name(&node.name.text)
+            .filter(|primitive| primitive.is_integer())
+            .expect("Function wasn't defined. This should have been caught by the type checker");
+        self.wrap(target.id());
+    }
+}
+
+impl Visit<BinaryExpression> for Generator {
+    type Output = ();
+
+    fn visit(&mut self, node: &BinaryExpression) -> Self::Output {
+        self.visit(node.lhs.as_ref());
+        // The right hand side of `&&` and `||` is only evaluated when the left doesn't decide the result.
+        match node.op {
+            BinaryOp::And => {
+                let short_circuit = self.placeholder_jump();
+                self.visit(node.rhs.as_ref());
+                let end = self.placeholder_jump();
+                self.patch_jump(short_circuit, Instruction::JumpIfFalse);
+                self.bytecode.push(Instruction::Push(0));
+                self.patch_jump(end, Instruction::Jump);
+                return;
+            }
+            BinaryOp::Or => {
+                let evaluate_rhs = self.placeholder_jump();
+                self.bytecode.push(Instruction::Push(1));
+                let end = self.placeholder_jump();
+                self.patch_jump(evaluate_rhs, Instruction::JumpIfFalse);
+                self.visit(node.rhs.as_ref());
+                self.patch_jump(end, Instruction::Jump);
+                return;
+            }
+            _ => {}
+        }
+        self.visit(node.rhs.as_ref());
+        if node.lhs.typed == Primitive::Float.id() {
+            self.bytecode.push(match node.op {
+                BinaryOp::Add => Instruction::FAdd,
+                BinaryOp::Sub => Instruction::FSub,
+                BinaryOp::Mul => Instruction::FMul,
+                BinaryOp::Div => Instruction::FDiv,
+                BinaryOp::Rem => Instruction::FRem,
+                BinaryOp::Equals => Instruction::FEq,
+                BinaryOp::NotEquals => Instruction::FNe,
+                BinaryOp::Less => Instruction::FLt,
+                BinaryOp::LessEquals => Instruction::FLe,
+                BinaryOp::Greater => Instruction::FGt,
+                BinaryOp::GreaterEquals => Instruction::FGe,
+                op => unreachable!("`{op}` can't be applied to floats. This should have been caught by the type checker"),
+            });
+            return;
+        }
+        let unsigned = Primitive::from_id(node.lhs.typed)
+            .is_some_and(|primitive| primitive.is_integer() && !primitive.is_signed());
+        self.bytecode.push(match node.op {
+            BinaryOp::Add => Instruction::Add,
+            BinaryOp::Sub => Instruction::Sub,
+            BinaryOp::Mul => Instruction::Mul,
+            BinaryOp::Div if unsigned => Instruction::DivU,
+            BinaryOp::Div => Instruction::Div,
+            BinaryOp::Rem if unsigned => Instruction::RemU,
+            BinaryOp::Rem => Instruction::Rem,
+            BinaryOp::Equals => Instruction::Eq,
+            BinaryOp::NotEquals => Instruction::Ne,
+            BinaryOp::Less if unsigned => Instruction::LtU,
+            BinaryOp::Less => Instruction::Lt,
+            BinaryOp::LessEquals if unsigned => Instruction::LeU,
+            BinaryOp::LessEquals => Instruction::Le,
+            BinaryOp::Greater if unsigned => Instruction::GtU,
+            BinaryOp::Greater => Instruction::Gt,
+            BinaryOp::GreaterEquals if unsigned => Instruction::GeU,
+            BinaryOp::GreaterEquals => Instruction::Ge,
+            BinaryOp::And | BinaryOp::Or => unreachable!("Logical operators short circuit"),
+            BinaryOp::BitAnd => Instruction::BitAnd,
+            BinaryOp::BitOr => Instruction::BitOr,
+            BinaryOp::BitXor => Instruction::BitXor,
+            BinaryOp::ShiftLeft => Instruction::Shl,
+            BinaryOp::ShiftRight if unsigned => Instruction::ShrU,
+            BinaryOp::ShiftRight => Instruction::Shr,
+        });
+        if matches!(
+            node.op,
+            BinaryOp::Add
+                | BinaryOp::Sub
+                | BinaryOp::Mul
+                | BinaryOp::Div
+                | BinaryOp::Rem
+                | BinaryOp::ShiftLeft
+        ) {
+            self.wrap(node.typed);
+        }
+    }
+}
+
+impl Visit<UnaryExpression> for Generator {
+    type Output = ();
+
+    fn visit(&mut self, node: &UnaryExpression) -> Self::Output {
+        self.visit(node.operand.as_ref());
+        self.bytecode.push(match node.op {
+            UnaryOp::Neg if node.operand.typed == Primitive::Float.id() => Instruction::FNeg,
+            UnaryOp::Neg => Instruction::Neg,
+            UnaryOp::Not => Instruction::Not,
+            UnaryOp::BitNot => Instruction::BitNot,
+        });
+        if node.op != UnaryOp::Not {
+            self.wrap(node.typed);
+        }
+    }
+}
+
 trait Visit<Node> {
     type Output;
 
@@ -1,28 +1,140 @@
+use rowdy_diagnostics::{DiagnosticSink, Lint, LintLevel, LintLevels};
 use rowdy_location::Source;
+use std::ffi::OsString;
 use std::io::Read;
 
 #[derive(Debug)]
 pub struct Config {
     pub source: Source,
+    /// The level each lint is reported at. Set with `-A name`, `-W name`, `-D name` and `-Werror`.
+    pub lints: LintLevels,
+    /// How to print diagnostics. Set with `--error-format=human|json`.
+    pub error_format: ErrorFormat,
+    /// Whether to color diagnostics. Set with `--color=auto|always|never`.
+    pub color: ColorChoice,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ErrorFormat {
+    /// rustc-style text with the source being pointed at.
+    #[default]
+    Human,
+    /// One JSON object per line, for tools like editors.
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Color diagnostics if they're going to a terminal and `NO_COLOR` isn't set.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// What the compiler was asked to do on the command line.
+#[derive(Debug)]
+pub enum Command {
+    /// Compile and run a program.
+    Run(Config),
+    /// Print the long-form explanation of an error code.
+    Explain(String),
+    /// Apply the fixes suggested by diagnostics to the program's source file.
+    Fix(Config),
+}
+
+impl Command {
+    pub fn new_from_args() -> Result<Command, &'static str> {
+        let mut args = std::env::args_os().skip(1).peekable(); // skip program name
+        if args.peek().is_some_and(|arg| arg == "--explain") {
+            args.next();
+            let code = match args.next().map(OsString::into_string) {
+                Some(Ok(code)) => code,
+                Some(Err(_)) => return Err("Error codes look like `E0001`"),
+                None => return Err("Didn't get an error code to explain"),
+            };
+            return Ok(Command::Explain(code));
+        }
+        if args.peek().is_some_and(|arg| arg == "fix") {
+            args.next();
+            return Config::from_args(args).map(Command::Fix);
+        }
+        Config::from_args(args).map(Command::Run)
+    }
 }
 
 impl Config {
-    pub fn new_from_args() -> Result<Config, &'static str> {
-        let mut args = std::env::args_os();
-        args.next(); // program name
-        let source = match args.next() {
-            Some(val) => Source::File(val),
-            None => return Err("Didn't get a filename"),
+    fn from_args(mut args: impl Iterator<Item = OsString>) -> Result<Config, &'static str> {
+        let mut source = None;
+        let mut lints = LintLevels::default();
+        let mut error_format = ErrorFormat::default();
+        let mut color = ColorChoice::default();
+        while let Some(arg) = args.next() {
+            if let Some((level, name)) = arg.to_str().and_then(lint_flag) {
+                // The lint's name can be part of the flag, like `-Wshadowing`, or the next argument.
+                let name = match name {
+                    "" => args
+                        .next()
+                        .ok_or("Didn't get a lint name after a lint flag")?,
+                    name => name.into(),
+                };
+                if level == LintLevel::Warn && name == "error" {
+                    lints.warnings_are_errors = true;
+                } else {
+                    let lint = name.to_str().and_then(Lint::from_name).ok_or(
+                        "Unknown lint, the lints are `unused_variable`, `unused_function`, `shadowing` and `unreachable_code`",
+                    )?;
+                    lints.set(lint, level);
+                }
+            } else if let Some(format) = arg
+                .to_str()
+                .and_then(|arg| arg.strip_prefix("--error-format="))
+            {
+                error_format = match format {
+                    "human" => ErrorFormat::Human,
+                    "json" => ErrorFormat::Json,
+                    _ => return Err("The error format must be `human` or `json`"),
+                };
+            } else if let Some(choice) = arg.to_str().and_then(|arg| arg.strip_prefix("--color=")) {
+                color = match choice {
+                    "auto" => ColorChoice::Auto,
+                    "always" => ColorChoice::Always,
+                    "never" => ColorChoice::Never,
+                    _ => return Err("The color choice must be `auto`, `always` or `never`"),
+                };
+            } else if source.is_none() {
+                source = Some(Source::File(arg));
+            } else {
+                return Err("Got more than one filename");
+            }
+        }
+        let Some(source) = source else {
+            return Err("Didn't get a filename");
         };
 
-        Ok(Config { source })
+        Ok(Config {
+            source,
+            lints,
+            error_format,
+            color,
+        })
     }
 }
 
+/// Splits a lint flag like `-Wshadowing` into the level it sets and the rest of the flag.
+fn lint_flag(arg: &str) -> Option<(LintLevel, &str)> {
+    let level = [LintLevel::Allow, LintLevel::Warn, LintLevel::Deny]
+        .into_iter()
+        .find(|level| arg.starts_with(level.flag()))?;
+    Some((level, &arg[level.flag().len()..]))
+}
+
 #[derive(Debug)]
 pub struct Compiler {
     pub config: Config,
     pub code: String,
+    /// Where every pass reports errors and warnings about the program.
+    pub diagnostics: DiagnosticSink,
 }
 
 impl Compiler {
@@ -30,6 +142,7 @@ impl Compiler {
         let mut c = Compiler {
             code: String::new(),
             config,
+            diagnostics: DiagnosticSink::new(),
         };
         match &c.config.source {
             Source::File(fname) => {
@@ -0,0 +1,169 @@
+use crate::suggestion::end_of;
+use crate::{Applicability, Diagnostic, Label};
+use rowdy_location::{Source, Span};
+use std::fmt::Write;
+
+impl Diagnostic {
+    /// Writes the diagnostic as a single line of JSON for tools to read, like:
+    ///
+    /// ```json
+    /// {"level":"error","code":"E0021","message":"mismatched types: expected `int`, found `float`",
+    /// "file":"main.ry","spans":[{"byte_start":8,"byte_end":11,"line_start":1,"column_start":9,
+    /// "line_end":1,"column_end":12,"is_primary":true,"label":"expected `int`, found `float`"}],
+    /// "children":[{"level":"note","message":"..."}],"suggestions":[{"message":"...",
+    /// "applicability":"machine-applicable","edits":[{"byte_start":8,"byte_end":11,"replacement":"1"}]}]}
+    /// ```
+    ///
+    /// The ends of spans and edits are exclusive, and columns count chars starting from 1.
+    /// `file` is null for a program which didn't come from a file.
+    pub fn to_json(&self, source: &Source, code: &str) -> String {
+        let mut json = String::new();
+        json.push_str("{\"level\":");
+        write_string(&mut json, &self.level.to_string());
+        json.push_str(",\"code\":");
+        write_string(&mut json, self.error_kind.code());
+        json.push_str(",\"message\":");
+        write_string(&mut json, &self.error_kind.to_string());
+        json.push_str(",\"file\":");
+        match source {
+            Source::File(_) => write_string(&mut json, &source.to_string()),
+            Source::Anonymous => json.push_str("null"),
+        }
+
+        json.push_str(",\"spans\":[");
+        for (i, label) in self.labels.iter().enumerate() {
+            if i != 0 {
+                json.push(',');
+            }
+            write_label(&mut json, label, code);
+        }
+
+        json.push_str("],\"children\":[");
+        for (i, (level, message)) in self.children.iter().enumerate() {
+            if i != 0 {
+                json.push(',');
+            }
+            json.push_str("{\"level\":");
+            write_string(&mut json, &level.to_string());
+            json.push_str(",\"message\":");
+            write_string(&mut json, message);
+            json.push('}');
+        }
+
+        json.push_str("],\"suggestions\":[");
+        for (i, suggestion) in self.suggestions.iter().enumerate() {
+            if i != 0 {
+                json.push(',');
+            }
+            json.push_str("{\"message\":");
+            write_string(&mut json, &suggestion.message);
+            json.push_str(",\"applicability\":");
+            write_string(
+                &mut json,
+                match suggestion.applicability {
+                    Applicability::MachineApplicable => "machine-applicable",
+                    Applicability::MaybeIncorrect => "maybe-incorrect",
+                },
+            );
+            json.push_str(",\"edits\":[");
+            for (i, edit) in suggestion.edits.iter().enumerate() {
+                if i != 0 {
+                    json.push(',');
+                }
+                let range = edit.byte_range(code);
+                let _ = write!(
+                    json,
+                    "{{\"byte_start\":{},\"byte_end\":{},\"replacement\":",
+                    range.start, range.end
+                );
+                write_string(&mut json, edit.text());
+                json.push('}');
+            }
+            json.push_str("]}");
+        }
+        json.push_str("]}");
+        json
+    }
+}
+
+fn write_label(json: &mut String, label: &Label, code: &str) {
+    let Span { start, end } = label.span;
+    let _ = write!(
+        json,
+        "{{\"byte_start\":{},\"byte_end\":{},\"line_start\":{},\"column_start\":{},\
+        \"line_end\":{},\"column_end\":{},\"is_primary\":{},\"label\":",
+        start.char_num,
+        end_of(label.span, code),
+        start.line,
+        start.col,
+        end.line,
+        end.col + 1,
+        label.primary,
+    );
+    match &label.message {
+        Some(message) => write_string(json, message),
+        None => json.push_str("null"),
+    }
+    json.push('}');
+}
+
+/// Writes `s` as a JSON string, quoted and escaped.
+fn write_string(json: &mut String, s: &str) {
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+#[test]
+fn strings_are_escaped() {
+    let mut json = String::new();
+    write_string(&mut json, "say \"hi\"\\\n\t\u{1}é");
+    assert_eq!(json, r#""say \"hi\"\\\n\t\u0001é""#);
+}
+
+#[test]
+fn diagnostics_become_json() {
+    let code = "int é = 1.5;\n";
+    let location = |col, char_num| rowdy_location::Location {
+        line: 1,
+        col,
+        char_num,
+    };
+    let diagnostic = Diagnostic::error(
+        Span::from_start_end(location(9, 9), location(11, 11)),
+        crate::ErrorKind::E0021 {
+            expected: "int".to_owned(),
+            found: "float".to_owned(),
+        },
+    )
+    .with_secondary(Span::from_loc(location(5, 4)), "expected due to this type")
+    .with_help("remove the `.5`")
+    .with_suggestion(
+        crate::Suggestion::new("use an int", Applicability::MachineApplicable)
+            .replace(Span::from_start_end(location(9, 9), location(11, 11)), "1"),
+    );
+    assert_eq!(
+        diagnostic.to_json(&Source::Anonymous, code),
+        concat!(
+            r#"{"level":"error","code":"E0021","message":"mismatched types: expected `int`, found `float`","#,
+            r#""file":null,"spans":["#,
+            r#"{"byte_start":9,"byte_end":12,"line_start":1,"column_start":9,"line_end":1,"column_end":12,"is_primary":true,"label":null},"#,
+            r#"{"byte_start":4,"byte_end":6,"line_start":1,"column_start":5,"line_end":1,"column_end":6,"is_primary":false,"label":"expected due to this type"}],"#,
+            r#""children":[{"level":"help","message":"remove the `.5`"}],"#,
+            r#""suggestions":[{"message":"use an int","applicability":"machine-applicable","#,
+            r#""edits":[{"byte_start":9,"byte_end":12,"replacement":"1"}]}]}"#,
+        )
+    );
+}
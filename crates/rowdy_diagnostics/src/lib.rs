@@ -1,67 +1,380 @@
-use rowdy_compiler::Compiler;
-use rowdy_location::Span;
+use rowdy_location::{Source, Span};
+use std::collections::BTreeSet;
 
-struct Diagnostic<'a> {
-    span: Span,
+mod json;
+mod lint;
+mod sink;
+mod suggestion;
+
+pub use lint::{Lint, LintLevel, LintLevels};
+pub use sink::DiagnosticSink;
+pub use suggestion::{apply_suggestions, Applicability, Edit, Suggestion};
+
+/// An error or warning about part of the program.
+/// Built with `Diagnostic::error` or `Diagnostic::warning`, then emitted to a `DiagnosticSink`.
+#[derive(Debug)]
+pub struct Diagnostic {
     error_kind: ErrorKind,
     level: Level,
-    compiler: &'a Compiler,
+    /// The parts of the source to show. The first label is the primary one.
+    labels: Vec<Label>,
+    /// `note:` and `help:` messages printed after the source.
+    children: Vec<(Level, String)>,
+    /// Changes which would fix the problem, printed as `help:` messages after the children.
+    suggestions: Vec<Suggestion>,
 }
 
-impl Diagnostic<'_> {
-    fn get_pad(&self) -> usize {
-        let last_line = self.span.end.line;
-        let mut padding = 0;
-        let mut line_counter = last_line;
-        while line_counter > 0 {
-            line_counter /= 10;
-            padding += 1;
+/// A span of the source pointed at by a diagnostic, with an optional message written next to it.
+#[derive(Debug)]
+struct Label {
+    span: Span,
+    message: Option<String>,
+    /// The primary label is where the problem is and is underlined with `^`.
+    /// Secondary labels give context and are underlined with `-`.
+    primary: bool,
+}
+
+impl Diagnostic {
+    pub fn error(span: Span, error_kind: ErrorKind) -> Self {
+        Self::new(span, error_kind, Level::Error)
+    }
+
+    pub fn warning(span: Span, error_kind: ErrorKind) -> Self {
+        Self::new(span, error_kind, Level::Warning)
+    }
+
+    fn new(span: Span, error_kind: ErrorKind, level: Level) -> Self {
+        Self {
+            error_kind,
+            level,
+            labels: vec![Label {
+                span,
+                message: None,
+                primary: true,
+            }],
+            children: Vec::new(),
+            suggestions: Vec::new(),
+        }
+    }
+
+    /// Writes `message` next to the primary span.
+    pub fn with_label(mut self, message: impl Into<String>) -> Self {
+        self.labels[0].message = Some(message.into());
+        self
+    }
+
+    /// Points at another span which helps explain the problem.
+    pub fn with_secondary(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            message: Some(message.into()),
+            primary: false,
+        });
+        self
+    }
+
+    pub fn with_note(mut self, message: impl Into<String>) -> Self {
+        self.children.push((Level::Note, message.into()));
+        self
+    }
+
+    pub fn with_help(mut self, message: impl Into<String>) -> Self {
+        self.children.push((Level::Help, message.into()));
+        self
+    }
+
+    pub fn with_suggestion(mut self, suggestion: Suggestion) -> Self {
+        self.suggestions.push(suggestion);
+        self
+    }
+
+    pub fn suggestions(&self) -> &[Suggestion] {
+        &self.suggestions
+    }
+
+    pub fn error_kind(&self) -> &ErrorKind {
+        &self.error_kind
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(self.level, Level::Error)
+    }
+
+    /// The primary span, which is where the problem is.
+    pub fn span(&self) -> Span {
+        self.labels[0].span
+    }
+
+    /// Shows the diagnostic rustc-style, along with the lines of `code` it's about.
+    /// If `colored` is set, ANSI escape codes color it in for a terminal.
+    pub fn display<'a>(
+        &'a self,
+        source: &'a Source,
+        code: &'a str,
+        colored: bool,
+    ) -> impl std::fmt::Display + 'a {
+        Rendered {
+            diagnostic: self,
+            source,
+            code,
+            colored,
         }
-        (padding + 1).max(4)
     }
 
-    fn pad_after_line_num(&self) -> usize {
-        let last_line = self.span.end.line;
-        let mut padding = 0;
-        let mut line_counter = last_line;
-        while line_counter > 0 {
-            line_counter /= 10;
-            padding += 1;
+    /// The numbers of the source lines to show, in order.
+    /// Only the first and last two lines of a long label are shown.
+    fn shown_lines(&self) -> BTreeSet<usize> {
+        let mut lines = BTreeSet::new();
+        for label in &self.labels {
+            let (start, end) = (label.span.start.line, label.span.end.line);
+            lines.extend([start, end]);
+            if end > start {
+                lines.extend([start + 1, end - 1]);
+            }
         }
-        self.get_pad() - padding
+        lines
     }
+}
+
+/// Where a label is underlined on one line of the source.
+struct Mark<'a> {
+    /// 0-based columns of the line.
+    cols: std::ops::RangeInclusive<usize>,
+    primary: bool,
+    message: Option<&'a str>,
+}
+
+/// A column of an annotation row, drawn in the given style.
+type Cell = Option<(char, Style)>;
 
-    #[allow(dead_code)]
-    fn write_padding(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let left_padding = self.get_pad();
-        f.write_str(&" ".repeat(left_padding))
+/// Puts `c` in every column of `cols`, leaving any others untouched.
+fn draw(row: &mut Vec<Cell>, cols: std::ops::RangeInclusive<usize>, c: char, style: Style) {
+    if row.len() <= *cols.end() {
+        row.resize(cols.end() + 1, None);
+    }
+    for col in cols {
+        row[col] = Some((c, style));
     }
 }
 
-impl std::fmt::Display for Diagnostic<'_> {
+/// An ANSI escape code setting how text looks.
+type Style = &'static str;
+
+const RESET: Style = "\x1b[0m";
+const BOLD: Style = "\x1b[1m";
+const BOLD_RED: Style = "\x1b[1;31m";
+const BOLD_GREEN: Style = "\x1b[1;32m";
+const BOLD_YELLOW: Style = "\x1b[1;33m";
+const BOLD_BLUE: Style = "\x1b[1;34m";
+const BOLD_CYAN: Style = "\x1b[1;36m";
+
+/// Text which is drawn in a style, if there is one.
+struct Painted<T> {
+    style: Option<Style>,
+    text: T,
+}
+
+impl<T: std::fmt::Display> std::fmt::Display for Painted<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.style {
+            Some(style) => write!(f, "{style}{}{RESET}", self.text),
+            None => write!(f, "{}", self.text),
+        }
+    }
+}
+
+struct Rendered<'a> {
+    diagnostic: &'a Diagnostic,
+    source: &'a Source,
+    code: &'a str,
+    colored: bool,
+}
+
+impl Rendered<'_> {
+    fn paint<T>(&self, style: Style, text: T) -> Painted<T> {
+        Painted {
+            style: self.colored.then_some(style),
+            text,
+        }
+    }
+
+    /// How the primary label is drawn. Secondary labels are always blue.
+    fn primary_style(&self) -> Style {
+        self.diagnostic.level.style()
+    }
+
+    /// Turns a row of annotation characters into a string which lines up with `line`,
+    /// keeping its tabs in the empty columns.
+    fn layout(&self, line: &str, row: &[Cell]) -> String {
+        let mut out = String::new();
+        let mut current_style = None;
+        let mut source = line.chars();
+        for cell in row {
+            let under = source.next();
+            let (c, style) = match *cell {
+                Some((c, style)) => (c, Some(style)),
+                None => (if under == Some('\t') { '\t' } else { ' ' }, None),
+            };
+            if self.colored && style != current_style {
+                out.push_str(style.unwrap_or(RESET));
+                current_style = style;
+            }
+            out.push(c);
+        }
+        if current_style.is_some() {
+            out.push_str(RESET);
+        }
+        out
+    }
+
+    /// Writes the underlines of every label touching `line_num` and the messages of those ending on it.
+    fn write_annotations(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        gutter: &str,
+        line_num: usize,
+        line: &str,
+    ) -> std::fmt::Result {
+        let mut marks = Vec::new();
+        for label in &self.diagnostic.labels {
+            let Span { start, end } = label.span;
+            if !(start.line..=end.line).contains(&line_num) {
+                continue;
+            }
+            let first_col = if start.line == line_num {
+                start.col - 1
+            } else {
+                line.chars().take_while(|c| c.is_whitespace()).count()
+            };
+            let (last_col, message) = if end.line == line_num {
+                (end.col - 1, label.message.as_deref())
+            } else {
+                (line.chars().count().saturating_sub(1), None)
+            };
+            marks.push(Mark {
+                cols: first_col..=last_col.max(first_col),
+                primary: label.primary,
+                message,
+            });
+        }
+        if marks.is_empty() {
+            return Ok(());
+        }
+        marks.sort_by_key(|mark| *mark.cols.start());
+        let style = |mark: &Mark| {
+            if mark.primary {
+                self.primary_style()
+            } else {
+                BOLD_BLUE
+            }
+        };
+
+        // Narrower marks are drawn on top of wider ones so that a label on part of another stays visible.
+        // Marks of the same width are drawn with the primary one on top.
+        let mut drawing_order: Vec<&Mark> = marks.iter().collect();
+        drawing_order
+            .sort_by_key(|mark| (std::cmp::Reverse(mark.cols.clone().count()), mark.primary));
+        let mut underline = Vec::new();
+        for mark in drawing_order {
+            let c = if mark.primary { '^' } else { '-' };
+            draw(&mut underline, mark.cols.clone(), c, style(mark));
+        }
+
+        let mut messages: Vec<(usize, &str, Style)> = marks
+            .iter()
+            .filter_map(|mark| Some((*mark.cols.start(), mark.message?, style(mark))))
+            .collect();
+        // The rightmost message fits on the same line as the underlines.
+        let inline = messages.pop();
+        write!(f, "{gutter} {}", self.layout(line, &underline))?;
+        match inline {
+            Some((_, message, style)) => writeln!(f, " {}", self.paint(style, message))?,
+            None => writeln!(f)?,
+        }
+
+        // The others hang below their marks, connected to them by `|`.
+        let mut connectors = Vec::new();
+        for &(col, _, style) in &messages {
+            draw(&mut connectors, col..=col, '|', style);
+        }
+        if !messages.is_empty() {
+            writeln!(f, "{gutter} {}", self.layout(line, &connectors))?;
+        }
+        while let Some((col, message, style)) = messages.pop() {
+            connectors.truncate(col);
+            writeln!(
+                f,
+                "{gutter} {}{}",
+                self.layout(line, &connectors),
+                self.paint(style, message)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Rendered<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let diagnostic = self.diagnostic;
+        let level = format!("{}[{}]", diagnostic.level, diagnostic.error_kind.code());
         writeln!(
             f,
-            "{}[{:?}]: {}",
-            self.level, self.error_kind, self.error_kind
+            "{}{}",
+            self.paint(self.primary_style(), level),
+            self.paint(BOLD, format!(": {}", diagnostic.error_kind))
         )?;
-        let pad = self.get_pad();
-        f.write_str(&" ".repeat(pad - 1))?;
-        writeln!(f, "--> {}:{}", self.compiler.config.source, self.span.start)?;
-        write!(
+
+        let lines = diagnostic.shown_lines();
+        let last_line = lines.last().copied().unwrap_or(1);
+        let pad = (last_line.to_string().len() + 1).max(4);
+        let gutter = self
+            .paint(BOLD_BLUE, format!("{}|", " ".repeat(pad)))
+            .to_string();
+        writeln!(
             f,
-            "{}{}| ",
-            self.span.start.line,
-            " ".repeat(self.pad_after_line_num())
+            "{}{} {}:{}",
+            " ".repeat(pad - 1),
+            self.paint(BOLD_BLUE, "-->"),
+            self.source,
+            diagnostic.span().start
         )?;
+        writeln!(f, "{gutter}")?;
 
-        for (i, line) in self.span.lines(&self.compiler.code).lines().enumerate() {
-            if i != 0 {
-                write!(f, "{}| ", " ".repeat(pad))?;
+        let source: Vec<&str> = self.code.lines().collect();
+        let mut previous = None;
+        for line_num in lines {
+            if previous.is_some_and(|previous| previous + 1 < line_num) {
+                writeln!(f, "{}", self.paint(BOLD_BLUE, "..."))?;
             }
-            writeln!(f, "{}", line)?;
+            previous = Some(line_num);
+            // The end of the file can be on the line after the last one.
+            let line = source.get(line_num - 1).copied().unwrap_or("");
+            let line_number = self.paint(BOLD_BLUE, format!("{line_num:<pad$}|"));
+            writeln!(f, "{line_number} {line}")?;
+            self.write_annotations(f, &gutter, line_num, line)?;
         }
 
+        if !diagnostic.children.is_empty() || !diagnostic.suggestions.is_empty() {
+            writeln!(f, "{gutter}")?;
+        }
+        let suggestions = diagnostic
+            .suggestions
+            .iter()
+            .map(|suggestion| (&Level::Help, &suggestion.message));
+        for (level, message) in diagnostic
+            .children
+            .iter()
+            .map(|(l, m)| (l, m))
+            .chain(suggestions)
+        {
+            writeln!(
+                f,
+                "{}{} {} {message}",
+                " ".repeat(pad),
+                self.paint(BOLD_BLUE, "="),
+                self.paint(level.style(), format!("{level}:"))
+            )?;
+        }
         Ok(())
     }
 }
@@ -70,6 +383,19 @@ impl std::fmt::Display for Diagnostic<'_> {
 enum Level {
     Error,
     Warning,
+    Note,
+    Help,
+}
+
+impl Level {
+    fn style(&self) -> Style {
+        match self {
+            Level::Error => BOLD_RED,
+            Level::Warning => BOLD_YELLOW,
+            Level::Note => BOLD_GREEN,
+            Level::Help => BOLD_CYAN,
+        }
+    }
 }
 
 impl std::fmt::Display for Level {
@@ -77,41 +403,371 @@ impl std::fmt::Display for Level {
         match self {
             Level::Error => f.write_str("error"),
             Level::Warning => f.write_str("warning"),
+            Level::Note => f.write_str("note"),
+            Level::Help => f.write_str("help"),
         }
     }
 }
 
-pub fn print_error(span: Span, message: ErrorKind, compiler: &Compiler) {
-    let diagnostic = Diagnostic {
-        span,
-        error_kind: message,
-        level: Level::Error,
-        compiler,
-    };
+#[derive(Debug)]
+pub enum ErrorKind {
+    E0001,
+    E0002 {
+        expected: &'static [&'static str],
+        found: String,
+    },
+    E0003,
+    E0004 {
+        found: char,
+    },
+    E0005,
+    E0006,
+    E0007,
+    E0008,
+    E0009,
+    E0010 {
+        radix: u32,
+    },
+    E0011,
+    E0012 {
+        suffix: String,
+    },
+    E0013,
+    E0014 {
+        name: String,
+    },
+    E0015 {
+        name: String,
+    },
+    E0016 {
+        name: String,
+    },
+    E0017 {
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+    E0018 {
+        expected: String,
+        found: String,
+    },
+    E0019 {
+        name: String,
+    },
+    E0020 {
+        name: String,
+    },
+    E0021 {
+        expected: String,
+        found: String,
+    },
+    E0022 {
+        op: String,
+        lhs: String,
+        rhs: String,
+    },
+    E0023 {
+        op: String,
+        operand: String,
+    },
+    E0024 {
+        name: String,
+    },
+    E0025 {
+        name: String,
+    },
+    E0026 {
+        name: String,
+    },
+    E0027 {
+        name: String,
+    },
+    E0028,
+    E0029 {
+        name: String,
+    },
+    E0030,
+    E0031 {
+        found: String,
+    },
+    E0032 {
+        name: String,
+    },
+    E0033 {
+        name: String,
+    },
+    E0034 {
+        typ: String,
+    },
+}
 
-    eprintln!("{diagnostic}");
+impl ErrorKind {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ErrorKind::E0001 => "E0001",
+            ErrorKind::E0002 { .. } => "E0002",
+            ErrorKind::E0003 => "E0003",
+            ErrorKind::E0004 { .. } => "E0004",
+            ErrorKind::E0005 => "E0005",
+            ErrorKind::E0006 => "E0006",
+            ErrorKind::E0007 => "E0007",
+            ErrorKind::E0008 => "E0008",
+            ErrorKind::E0009 => "E0009",
+            ErrorKind::E0010 { .. } => "E0010",
+            ErrorKind::E0011 => "E0011",
+            ErrorKind::E0012 { .. } => "E0012",
+            ErrorKind::E0013 => "E0013",
+            ErrorKind::E0014 { .. } => "E0014",
+            ErrorKind::E0015 { .. } => "E0015",
+            ErrorKind::E0016 { .. } => "E0016",
+            ErrorKind::E0017 { .. } => "E0017",
+            ErrorKind::E0018 { .. } => "E0018",
+            ErrorKind::E0019 { .. } => "E0019",
+            ErrorKind::E0020 { .. } => "E0020",
+            ErrorKind::E0021 { .. } => "E0021",
+            ErrorKind::E0022 { .. } => "E0022",
+            ErrorKind::E0023 { .. } => "E0023",
+            ErrorKind::E0024 { .. } => "E0024",
+            ErrorKind::E0025 { .. } => "E0025",
+            ErrorKind::E0026 { .. } => "E0026",
+            ErrorKind::E0027 { .. } => "E0027",
+            ErrorKind::E0028 => "E0028",
+            ErrorKind::E0029 { .. } => "E0029",
+            ErrorKind::E0030 => "E0030",
+            ErrorKind::E0031 { .. } => "E0031",
+            ErrorKind::E0032 { .. } => "E0032",
+            ErrorKind::E0033 { .. } => "E0033",
+            ErrorKind::E0034 { .. } => "E0034",
+        }
+    }
 }
 
-pub fn print_warning(span: Span, message: ErrorKind, compiler: &Compiler) {
-    let diagnostic = Diagnostic {
-        span,
-        error_kind: message,
-        level: Level::Warning,
-        compiler,
-    };
+impl std::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErrorKind::E0001 => f.write_str("parameter name used more than once"),
+            ErrorKind::E0002 { expected, found } => {
+                f.write_str("expected ")?;
+                match expected {
+                    [] => f.write_str("nothing")?,
+                    [only] => f.write_str(only)?,
+                    [first, rest @ ..] => {
+                        write!(f, "one of {first}")?;
+                        for option in rest {
+                            write!(f, ", {option}")?;
+                        }
+                    }
+                }
+                write!(f, ", found {found}")
+            }
+            ErrorKind::E0003 => f.write_str("unterminated block comment"),
+            ErrorKind::E0004 { found } => {
+                write!(f, "unexpected character `{}`", found.escape_debug())
+            }
+            ErrorKind::E0005 => f.write_str("integer literal is too large for `int`"),
+            ErrorKind::E0006 => f.write_str("unterminated string literal"),
+            ErrorKind::E0007 => f.write_str("unterminated character literal"),
+            ErrorKind::E0008 => f.write_str("unknown escape sequence"),
+            ErrorKind::E0009 => f.write_str("character literal must hold exactly one character"),
+            ErrorKind::E0010 { radix } => write!(f, "invalid digit for a base {radix} literal"),
+            ErrorKind::E0011 => f.write_str("number literal has no digits"),
+            ErrorKind::E0012 { suffix } => {
+                write!(f, "invalid suffix `{suffix}` for number literal")
+            }
+            ErrorKind::E0013 => f.write_str("float literal is too large for `float`"),
+            ErrorKind::E0014 { name } => write!(f, "cannot find type `{name}`"),
+            ErrorKind::E0015 { name } => write!(f, "cannot find variable `{name}` in this scope"),
+            ErrorKind::E0016 { name } => write!(f, "cannot find function `{name}`"),
+            ErrorKind::E0017 {
+                name,
+                expected,
+                found,
+            } => {
+                let plural = |count: &usize| if *count == 1 { "" } else { "s" };
+                write!(
+                    f,
+                    "function `{name}` takes {expected} argument{} but {found} {} supplied",
+                    plural(expected),
+                    if *found == 1 { "was" } else { "were" }
+                )
+            }
+            ErrorKind::E0018 { expected, found } => {
+                write!(
+                    f,
+                    "mismatched argument type: expected `{expected}`, found `{found}`"
+                )
+            }
+            ErrorKind::E0019 { name } => {
+                write!(f, "variable `{name}` is already declared in this scope")
+            }
+            ErrorKind::E0020 { name } => {
+                write!(f, "`{name}` shadows a variable from an outer scope")
+            }
+            ErrorKind::E0021 { expected, found } => {
+                write!(
+                    f,
+                    "mismatched types: expected `{expected}`, found `{found}`"
+                )
+            }
+            ErrorKind::E0022 { op, lhs, rhs } => {
+                write!(f, "cannot apply `{op}` to `{lhs}` and `{rhs}`")
+            }
+            ErrorKind::E0023 { op, operand } => {
+                write!(f, "cannot apply unary `{op}` to `{operand}`")
+            }
+            ErrorKind::E0024 { name } => write!(f, "cannot find attribute `{name}`"),
+            ErrorKind::E0025 { name } => write!(f, "unknown lint: `{name}`"),
+            ErrorKind::E0026 { name } => write!(f, "unused variable: `{name}`"),
+            ErrorKind::E0027 { name } => write!(f, "function `{name}` is never used"),
+            ErrorKind::E0028 => write!(f, "unreachable statement"),
+            ErrorKind::E0029 { name } => write!(f, "function `{name}` is defined more than once"),
+            ErrorKind::E0030 => write!(f, "`main` function not found"),
+            ErrorKind::E0031 { found } => {
+                write!(
+                    f,
+                    "`main` must be declared as `int main()`, found `{found}`"
+                )
+            }
+            ErrorKind::E0032 { name } => write!(f, "parameter `{name}` has type `void`"),
+            ErrorKind::E0033 { name } => {
+                write!(f, "function `{name}` can end without returning a value")
+            }
+            ErrorKind::E0034 { typ } => write!(f, "literal out of range for `{typ}`"),
+        }
+    }
+}
 
-    eprintln!("{diagnostic}");
+macro_rules! explanations {
+    ($($code:ident),* $(,)?) => {
+        /// The long-form explanation of an error code, as printed by `rowdy --explain`.
+        pub fn explain(code: &str) -> Option<&'static str> {
+            match code {
+                $(stringify!($code) => {
+                    Some(include_str!(concat!("explanations/", stringify!($code), ".md")))
+                })*
+                _ => None,
+            }
+        }
+    };
 }
 
-#[derive(Debug)]
-pub enum ErrorKind {
-    E0000,
+explanations!(
+    E0001, E0002, E0003, E0004, E0005, E0006, E0007, E0008, E0009, E0010, E0011, E0012, E0013,
+    E0014, E0015, E0016, E0017, E0018, E0019, E0020, E0021, E0022, E0023, E0024, E0025, E0026,
+    E0027, E0028, E0029, E0030, E0031, E0032, E0033, E0034,
+);
+
+/// Picks the candidate closest to `name` by edit distance, as long as it's close enough
+/// to plausibly be what was meant.
+pub fn did_you_mean<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
 }
 
-impl std::fmt::Display for ErrorKind {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            ErrorKind::E0000 => f.write_str("unknown error"),
+/// The Levenshtein distance between `a` and `b`:
+/// how many single character insertions, deletions or substitutions it takes to turn one into the other.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    // Distances from the prefix of `a` seen so far to every prefix of `b`.
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = diagonal + (a_char != *b_char) as usize;
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
         }
     }
+    row[b.len()]
+}
+
+#[test]
+fn suggestions() {
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+    assert_eq!(edit_distance("", "abc"), 3);
+    assert_eq!(
+        did_you_mean("countr", ["count", "counter", "x"]),
+        Some("count")
+    );
+    assert_eq!(did_you_mean("y", ["x"]), Some("x"));
+    assert_eq!(did_you_mean("foo", ["bar", "baz"]), None);
+}
+
+#[test]
+fn every_code_is_explained() {
+    for code in 1..=34 {
+        assert!(explain(&format!("E{code:04}")).is_some());
+    }
+    assert_eq!(explain("E9999"), None);
+}
+
+#[test]
+fn labels_are_underlined() {
+    let span = |start, end| {
+        let location = |col| rowdy_location::Location {
+            line: 1,
+            col,
+            char_num: col - 1,
+        };
+        Span::from_start_end(location(start), location(end))
+    };
+    let diagnostic = Diagnostic::error(
+        span(9, 11),
+        ErrorKind::E0021 {
+            expected: "int".to_owned(),
+            found: "float".to_owned(),
+        },
+    )
+    .with_label("expected `int`, found `float`")
+    .with_secondary(span(1, 3), "expected due to this type")
+    .with_help("remove the `.5`");
+    assert_eq!(
+        diagnostic
+            .display(&Source::Anonymous, "int x = 1.5;\n", false)
+            .to_string(),
+        "\
+error[E0021]: mismatched types: expected `int`, found `float`
+   --> unknown:1:9
+    |
+1   | int x = 1.5;
+    | ---     ^^^ expected `int`, found `float`
+    | |
+    | expected due to this type
+    |
+    = help: remove the `.5`
+"
+    );
+}
+
+#[test]
+fn colors_are_optional() {
+    let location = rowdy_location::Location {
+        line: 1,
+        col: 1,
+        char_num: 0,
+    };
+    let diagnostic = Diagnostic::warning(
+        Span::from_loc(location),
+        ErrorKind::E0020 {
+            name: "x".to_owned(),
+        },
+    );
+    let colored = diagnostic
+        .display(&Source::Anonymous, "x", true)
+        .to_string();
+    assert!(colored.starts_with("\x1b[1;33mwarning[E0020]\x1b[0m"));
+    assert!(colored.contains("\x1b[1;33m^\x1b[0m"));
+    let plain = diagnostic
+        .display(&Source::Anonymous, "x", false)
+        .to_string();
+    assert!(!plain.contains('\x1b'));
 }
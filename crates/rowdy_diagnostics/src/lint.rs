@@ -0,0 +1,137 @@
+/// A check for code which compiles but is probably a mistake.
+/// Each one is reported at a level which can be changed on the command line,
+/// or allowed for one function with `#[allow(name)]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lint {
+    UnusedVariable,
+    UnusedFunction,
+    Shadowing,
+    UnreachableCode,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 4] = [
+        Lint::UnusedVariable,
+        Lint::UnusedFunction,
+        Lint::Shadowing,
+        Lint::UnreachableCode,
+    ];
+
+    /// The name used for the lint in flags and attributes, like `unused_variable`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Lint::UnusedVariable => "unused_variable",
+            Lint::UnusedFunction => "unused_function",
+            Lint::Shadowing => "shadowing",
+            Lint::UnreachableCode => "unreachable_code",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Lint> {
+        Lint::ALL.into_iter().find(|lint| lint.name() == name)
+    }
+
+    pub fn default_level(self) -> LintLevel {
+        match self {
+            Lint::UnusedVariable | Lint::UnusedFunction | Lint::UnreachableCode => LintLevel::Warn,
+            Lint::Shadowing => LintLevel::Allow,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintLevel {
+    /// Don't report the lint at all.
+    Allow,
+    /// Report the lint as a warning.
+    Warn,
+    /// Report the lint as an error, so the program fails to compile.
+    Deny,
+}
+
+impl LintLevel {
+    /// The command line flag which sets a lint to this level.
+    pub fn flag(self) -> &'static str {
+        match self {
+            LintLevel::Allow => "-A",
+            LintLevel::Warn => "-W",
+            LintLevel::Deny => "-D",
+        }
+    }
+}
+
+/// The level every lint is reported at. Set with `-A`, `-W`, `-D` and `-Werror`.
+#[derive(Debug, Clone)]
+pub struct LintLevels {
+    levels: [LintLevel; Lint::ALL.len()],
+    /// Report lints which would be warnings as errors instead.
+    pub warnings_are_errors: bool,
+}
+
+impl Default for LintLevels {
+    fn default() -> Self {
+        Self {
+            levels: Lint::ALL.map(Lint::default_level),
+            warnings_are_errors: false,
+        }
+    }
+}
+
+impl LintLevels {
+    /// Every lint at the given level, such as to check a program without any lints.
+    pub fn all(level: LintLevel) -> Self {
+        Self {
+            levels: [level; Lint::ALL.len()],
+            warnings_are_errors: false,
+        }
+    }
+
+    pub fn set(&mut self, lint: Lint, level: LintLevel) {
+        self.levels[lint as usize] = level;
+    }
+
+    /// The level `lint` is reported at, accounting for `-Werror`.
+    pub fn level(&self, lint: Lint) -> LintLevel {
+        match self.levels[lint as usize] {
+            LintLevel::Warn if self.warnings_are_errors => LintLevel::Deny,
+            level => level,
+        }
+    }
+
+    /// Explains why `lint` is reported at its level, for a note on its diagnostics.
+    pub fn reason(&self, lint: Lint) -> String {
+        let set_level = self.levels[lint as usize];
+        let name = lint.name();
+        if set_level == LintLevel::Warn && self.warnings_are_errors {
+            format!("`-D {name}` implied by `-Werror`")
+        } else if set_level == lint.default_level() {
+            format!("`{name}` is on by default")
+        } else {
+            format!(
+                "requested on the command line with `{} {name}`",
+                set_level.flag()
+            )
+        }
+    }
+}
+
+#[test]
+fn lint_levels() {
+    for lint in Lint::ALL {
+        assert_eq!(Lint::from_name(lint.name()), Some(lint));
+    }
+    assert_eq!(Lint::from_name("unused"), None);
+
+    let mut levels = LintLevels::default();
+    assert_eq!(levels.level(Lint::Shadowing), LintLevel::Allow);
+    levels.set(Lint::Shadowing, LintLevel::Warn);
+    levels.warnings_are_errors = true;
+    assert_eq!(levels.level(Lint::Shadowing), LintLevel::Deny);
+    assert_eq!(levels.level(Lint::UnusedVariable), LintLevel::Deny);
+    assert_eq!(
+        levels.reason(Lint::UnusedVariable),
+        "`-D unused_variable` implied by `-Werror`"
+    );
+    levels.set(Lint::UnusedFunction, LintLevel::Allow);
+    assert_eq!(levels.level(Lint::UnusedFunction), LintLevel::Allow);
+}
@@ -0,0 +1,51 @@
+use crate::Diagnostic;
+use std::cell::{Cell, Ref, RefCell};
+
+/// Collects the diagnostics emitted while compiling a program, counting errors and warnings.
+/// Every pass shares the compiler immutably, so emitting only takes `&self`.
+#[derive(Debug, Default)]
+pub struct DiagnosticSink {
+    diagnostics: RefCell<Vec<Diagnostic>>,
+    error_count: Cell<usize>,
+    warning_count: Cell<usize>,
+}
+
+impl DiagnosticSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn emit(&self, diagnostic: Diagnostic) {
+        let count = if diagnostic.is_error() {
+            &self.error_count
+        } else {
+            &self.warning_count
+        };
+        count.set(count.get() + 1);
+        self.diagnostics.borrow_mut().push(diagnostic);
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.error_count.get()
+    }
+
+    pub fn warning_count(&self) -> usize {
+        self.warning_count.get()
+    }
+
+    /// Whether the program has failed to compile.
+    pub fn has_errors(&self) -> bool {
+        self.error_count() > 0
+    }
+
+    /// The diagnostics which have been emitted but not taken yet, in the order they were emitted.
+    pub fn diagnostics(&self) -> Ref<'_, [Diagnostic]> {
+        Ref::map(self.diagnostics.borrow(), Vec::as_slice)
+    }
+
+    /// Removes every diagnostic emitted so far, such as to print them.
+    /// They're still included in the counts.
+    pub fn take(&self) -> Vec<Diagnostic> {
+        self.diagnostics.take()
+    }
+}
@@ -0,0 +1,146 @@
+use rowdy_location::Span;
+use std::ops::Range;
+
+/// A change to the program which would fix the problem a diagnostic is about.
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    pub message: String,
+    pub edits: Vec<Edit>,
+    pub applicability: Applicability,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Applicability {
+    /// The fix is almost certainly what was meant, so `rowdy fix` applies it.
+    MachineApplicable,
+    /// The fix may change what the program means, so it's only shown.
+    MaybeIncorrect,
+}
+
+#[derive(Debug, Clone)]
+pub enum Edit {
+    /// Replaces the text of the span.
+    Replace { span: Span, text: String },
+    /// Inserts text right after the span.
+    InsertAfter { span: Span, text: String },
+}
+
+impl Suggestion {
+    pub fn new(message: impl Into<String>, applicability: Applicability) -> Self {
+        Self {
+            message: message.into(),
+            edits: Vec::new(),
+            applicability,
+        }
+    }
+
+    pub fn replace(mut self, span: Span, text: impl Into<String>) -> Self {
+        self.edits.push(Edit::Replace {
+            span,
+            text: text.into(),
+        });
+        self
+    }
+
+    pub fn insert_after(mut self, span: Span, text: impl Into<String>) -> Self {
+        self.edits.push(Edit::InsertAfter {
+            span,
+            text: text.into(),
+        });
+        self
+    }
+}
+
+impl Edit {
+    /// The bytes of `code` the edit replaces, which are empty for an insertion.
+    pub fn byte_range(&self, code: &str) -> Range<usize> {
+        match self {
+            Edit::Replace { span, .. } => span.start.char_num..end_of(*span, code),
+            Edit::InsertAfter { span, .. } => {
+                let end = end_of(*span, code);
+                end..end
+            }
+        }
+    }
+
+    /// The text the edit puts in the code.
+    pub fn text(&self) -> &str {
+        match self {
+            Edit::Replace { text, .. } | Edit::InsertAfter { text, .. } => text,
+        }
+    }
+}
+
+/// The byte just after `span`. Spans include the char at their end, which may be more than one byte long.
+pub(crate) fn end_of(span: Span, code: &str) -> usize {
+    let end_len = code
+        .get(span.end.char_num..)
+        .and_then(|rest| rest.chars().next())
+        .map_or(1, char::len_utf8);
+    span.end.char_num + end_len
+}
+
+/// Applies every machine-applicable suggestion to `code`, returning the new code
+/// along with how many suggestions were applied.
+/// A suggestion with an edit overlapping one which was already applied is skipped,
+/// leaving it to be fixed by running this again.
+pub fn apply_suggestions<'a>(
+    code: &str,
+    suggestions: impl IntoIterator<Item = &'a Suggestion>,
+) -> (String, usize) {
+    let mut edits: Vec<(Range<usize>, &str)> = Vec::new();
+    let mut applied = 0;
+    for suggestion in suggestions {
+        if suggestion.applicability != Applicability::MachineApplicable {
+            continue;
+        }
+        let ranges: Vec<_> = suggestion
+            .edits
+            .iter()
+            .map(|edit| (edit.byte_range(code), edit.text()))
+            .collect();
+        let overlaps = ranges.iter().any(|(range, _)| {
+            edits.iter().any(|(other, _)| {
+                range.start < other.end && other.start < range.end || range.start == other.start
+            })
+        });
+        if !overlaps {
+            edits.extend(ranges);
+            applied += 1;
+        }
+    }
+
+    edits.sort_by_key(|(range, _)| range.start);
+    let mut fixed = String::with_capacity(code.len());
+    let mut copied_up_to = 0;
+    for (range, text) in edits {
+        fixed.push_str(&code[copied_up_to..range.start]);
+        fixed.push_str(text);
+        copied_up_to = range.end;
+    }
+    fixed.push_str(&code[copied_up_to..]);
+    (fixed, applied)
+}
+
+#[test]
+fn suggestions_are_applied() {
+    let code = "int main() { int count = 1; return cuont }";
+    let location = |col: usize| rowdy_location::Location {
+        line: 1,
+        col,
+        char_num: col - 1,
+    };
+    let span = |start, end| Span::from_start_end(location(start), location(end));
+    let typo = Suggestion::new("fix the typo", Applicability::MachineApplicable)
+        .replace(span(36, 40), "count");
+    let semicolon = Suggestion::new("add a `;`", Applicability::MachineApplicable)
+        .insert_after(span(36, 40), ";");
+    let conflicting = Suggestion::new("fix it differently", Applicability::MachineApplicable)
+        .replace(span(37, 38), "ou");
+    let unsure =
+        Suggestion::new("remove the type", Applicability::MaybeIncorrect).replace(span(14, 17), "");
+    assert_eq!(
+        apply_suggestions(code, [&typo, &semicolon, &conflicting, &unsure]),
+        ("int main() { int count = 1; return count; }".to_owned(), 2)
+    );
+}
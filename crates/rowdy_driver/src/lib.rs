@@ -1,31 +1,362 @@
+use rowdy_ast::typed;
 use rowdy_codegen::generate_bytecode;
-use rowdy_compiler::{Compiler, Config};
+use rowdy_compiler::{ColorChoice, Compiler, Config, ErrorFormat};
+use rowdy_diagnostics as diagnostic;
+use rowdy_interpreter::interpret_bytecode;
+use rowdy_lexer::token::{LexError, QualifiedTokenType};
 use rowdy_lexer::tokenize;
+use rowdy_location::Source;
 use rowdy_parser::parse_tokens;
 use rowdy_type_checking::type_check;
-use rowdy_interpreter::interpret_bytecode;
+use std::io::IsTerminal;
+
+/// Prints the long-form explanation of an error code like `E0001`.
+pub fn explain(code: &str) -> i32 {
+    match diagnostic::explain(&code.to_ascii_uppercase()) {
+        Some(explanation) => {
+            print!("{explanation}");
+            0
+        }
+        None => {
+            eprintln!("error: `{code}` is not a valid error code");
+            1
+        }
+    }
+}
 
-pub fn run(config: Config) {
+/// Compiles and runs the program, returning the value returned by its `main` function.
+/// If the program fails to compile, the errors are printed and a non-zero value is returned.
+pub fn run(config: Config) -> i32 {
     let compiler = Compiler::new(config).expect("TODO: handle errors here");
-    let tokens = tokenize(&compiler);
+    run_compiler(&compiler)
+}
+
+/// Compiles and runs the program which has already been read into `compiler`.
+fn run_compiler(compiler: &Compiler) -> i32 {
+    let typed_ast = check(compiler);
+    if print_diagnostics(compiler) {
+        return 1;
+    }
+    let typed_ast = typed_ast.expect("The program was checked without errors");
+    let bytecode = generate_bytecode(&typed_ast);
+    interpret_bytecode(bytecode)
+}
 
-    // for t in tokens.clone() {
-    //     println!("{t:?}");
-    // }
+/// Applies every machine-applicable suggestion from the program's diagnostics to its source file,
+/// returning a non-zero value if the file couldn't be fixed.
+pub fn fix(config: Config) -> i32 {
+    let mut compiler = Compiler::new(config).expect("TODO: handle errors here");
+    let Source::File(path) = compiler.config.source.clone() else {
+        eprintln!("error: only programs read from a file can be fixed");
+        return 1;
+    };
+    let total = fix_code(&mut compiler);
+    if total > 0 {
+        if let Err(e) = std::fs::write(path, &compiler.code) {
+            eprintln!("error: couldn't write to {}: {e}", compiler.config.source);
+            return 1;
+        }
+    }
+    let plural = if total == 1 { "" } else { "es" };
+    eprintln!("applied {total} fix{plural} to {}", compiler.config.source);
+    0
+}
 
-    let mut ast = parse_tokens(tokens, &compiler);
-    // dbg!(&ast);
+/// Applies the machine-applicable suggestions to `compiler.code`, returning how many were applied.
+/// The program is checked again after each round of fixes, since only the first syntax error
+/// can be trusted to be fixed and fixes can overlap,
+/// until there's nothing left to fix or it's done [`MAX_FIX_ROUNDS`] rounds.
+fn fix_code(compiler: &mut Compiler) -> usize {
+    let mut total = 0;
+    for _ in 0..MAX_FIX_ROUNDS {
+        check(compiler);
+        let diagnostics = compiler.diagnostics.take();
+        let suggestions = diagnostics.iter().flat_map(diagnostic::Diagnostic::suggestions);
+        let (fixed, applied) = diagnostic::apply_suggestions(&compiler.code, suggestions);
+        if applied == 0 {
+            break;
+        }
+        total += applied;
+        compiler.code = fixed;
+        compiler.diagnostics = diagnostic::DiagnosticSink::new();
+    }
+    total
+}
 
-    let typed_ast = type_check(&mut ast, &compiler);
-    // TODO: Only do codegen if type checking succeded.
-    let bytecode = generate_bytecode(&typed_ast);
-    interpret_bytecode(bytecode);
+/// Stops `rowdy fix` from going forever if a suggestion doesn't fix the problem it's for.
+const MAX_FIX_ROUNDS: usize = 16;
+
+/// Lexes, parses and type checks the program, emitting diagnostics for anything wrong with it.
+/// Returns the typed program if it got as far as type checking.
+fn check(compiler: &Compiler) -> Option<typed::Ast> {
+    let tokens = tokenize(compiler);
+
+    for token in tokens.clone() {
+        if let QualifiedTokenType::Error(error) = token.typ {
+            let kind = match error {
+                LexError::UnterminatedBlockComment => diagnostic::ErrorKind::E0003,
+                LexError::UnexpectedChar(found) => diagnostic::ErrorKind::E0004 { found },
+                LexError::IntOutOfRange => diagnostic::ErrorKind::E0005,
+                LexError::FloatOutOfRange => diagnostic::ErrorKind::E0013,
+                LexError::InvalidDigit { radix } => diagnostic::ErrorKind::E0010 { radix },
+                LexError::NoDigits => diagnostic::ErrorKind::E0011,
+                LexError::InvalidSuffix(suffix) => diagnostic::ErrorKind::E0012 { suffix },
+                LexError::UnterminatedString => diagnostic::ErrorKind::E0006,
+                LexError::UnterminatedChar => diagnostic::ErrorKind::E0007,
+                LexError::InvalidEscape => diagnostic::ErrorKind::E0008,
+                LexError::CharLitLength => diagnostic::ErrorKind::E0009,
+            };
+            compiler
+                .diagnostics
+                .emit(diagnostic::Diagnostic::error(token.span, kind));
+        }
+    }
+
+    let ast = match parse_tokens(tokens, compiler) {
+        Ok(ast) => ast,
+        Err(errors) => {
+            for (i, error) in errors.into_iter().enumerate() {
+                let mut diagnostic = diagnostic::Diagnostic::error(
+                    error.span(),
+                    diagnostic::ErrorKind::E0002 {
+                        expected: error.expected(),
+                        found: error.found(),
+                    },
+                );
+                if let (["`;`"], Some(previous)) = (error.expected(), error.previous_span()) {
+                    // Later errors may only be there because of how the parser recovered from the first.
+                    let applicability = if i == 0 {
+                        diagnostic::Applicability::MachineApplicable
+                    } else {
+                        diagnostic::Applicability::MaybeIncorrect
+                    };
+                    diagnostic = diagnostic.with_suggestion(
+                        diagnostic::Suggestion::new("add the missing `;`", applicability)
+                        .insert_after(previous, ";"),
+                    );
+                }
+                compiler.diagnostics.emit(diagnostic);
+            }
+            return None;
+        }
+    };
+    // The parser skips over lexer errors, so the program can fail to compile without a parse error.
+    if compiler.diagnostics.has_errors() {
+        return None;
+    }
+
+    Some(type_check(&ast, compiler))
+}
+
+/// Prints the diagnostics emitted since this was last called.
+/// Returns whether the program has failed to compile.
+fn print_diagnostics(compiler: &Compiler) -> bool {
+    let (source, code) = (&compiler.config.source, &compiler.code);
+    let colored = use_color(compiler.config.color);
+    for diagnostic in compiler.diagnostics.take() {
+        match compiler.config.error_format {
+            ErrorFormat::Human => eprintln!("{}", diagnostic.display(source, code, colored)),
+            ErrorFormat::Json => eprintln!("{}", diagnostic.to_json(source, code)),
+        }
+    }
+    compiler.diagnostics.has_errors()
+}
+
+/// Whether diagnostics printed to stderr should be colored.
+/// Following <https://no-color.org>, a non-empty `NO_COLOR` turns off automatic coloring.
+fn use_color(choice: ColorChoice) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+            !no_color && std::io::stderr().is_terminal()
+        }
+    }
 }
 
 #[test]
 fn compile_testry() {
     let config = Config {
-        source: Source::File("./test.ry".into()),
+        source: Source::File(concat!(env!("CARGO_MANIFEST_DIR"), "/../../test.ry").into()),
+        lints: diagnostic::LintLevels::default(),
+        error_format: ErrorFormat::Human,
+        color: ColorChoice::Never,
+    };
+    assert_eq!(run(config), 0);
+}
+
+/// Compiles and runs `code`, returning the value returned by its `main` function.
+#[cfg(test)]
+fn run_str(code: &str) -> i32 {
+    let compiler = Compiler {
+        config: Config {
+            source: Source::Anonymous,
+            lints: diagnostic::LintLevels::all(diagnostic::LintLevel::Allow),
+            error_format: ErrorFormat::Human,
+            color: ColorChoice::Never,
+        },
+        code: code.to_owned(),
+        diagnostics: diagnostic::DiagnosticSink::new(),
     };
-    run(config);
+    run_compiler(&compiler)
+}
+
+/// Applies every fix `rowdy fix` would to `code`, returning the fixed code and how many fixes were applied.
+/// Also checks that the fixed code compiles.
+#[cfg(test)]
+fn fix_str(code: &str) -> (String, usize) {
+    let mut compiler = Compiler {
+        config: Config {
+            source: Source::Anonymous,
+            lints: diagnostic::LintLevels::all(diagnostic::LintLevel::Allow),
+            error_format: ErrorFormat::Human,
+            color: ColorChoice::Never,
+        },
+        code: code.to_owned(),
+        diagnostics: diagnostic::DiagnosticSink::new(),
+    };
+    let applied = fix_code(&mut compiler);
+    assert!(check(&compiler).is_some() && !compiler.diagnostics.has_errors());
+    (compiler.code, applied)
+}
+
+#[test]
+fn fixes_are_applied() {
+    assert_eq!(
+        fix_str("int main() { int x = 1 return x; }"),
+        ("int main() { int x = 1; return x; }".to_owned(), 1)
+    );
+    assert_eq!(
+        fix_str("int main() { int x = 1 int y = 2 return x + y; }"),
+        ("int main() { int x = 1; int y = 2; return x + y; }".to_owned(), 2)
+    );
+    assert_eq!(
+        fix_str("int main() { for (int i = 0 i < 3; i = i + 1) { } return 0; }"),
+        ("int main() { for (int i = 0; i < 3; i = i + 1) { } return 0; }".to_owned(), 1)
+    );
+}
+
+#[test]
+fn control_flow_runs() {
+    assert_eq!(
+        run_str("int main() { if (1 < 2) { return 1; } else { return 2; } }"),
+        1
+    );
+    assert_eq!(
+        run_str("int main() { if (false) { return 1; } else if (true) { return 2; } return 3; }"),
+        2
+    );
+    assert_eq!(
+        run_str("int main() { int n = 0; while (n < 10) { n = n + 3; } return n; }"),
+        12
+    );
+    assert_eq!(
+        run_str("int main() { int sum = 0; for (int i = 1; i <= 4; i = i + 1) { sum = sum + i; } return sum; }"),
+        10
+    );
+}
+
+#[test]
+fn logical_operators_short_circuit() {
+    // Dividing by zero stops the program with an exit code of -1,
+    // so these only return normally if the right hand side is never evaluated.
+    assert_eq!(
+        run_str("int main() { if (false && 1 / 0 == 0) { return 1; } return 2; }"),
+        2
+    );
+    assert_eq!(
+        run_str("int main() { if (true || 1 / 0 == 0) { return 1; } return 2; }"),
+        1
+    );
+    assert_eq!(run_str("int main() { if (true && 1 / 0 == 0) { return 1; } return 2; }"), -1);
+}
+
+#[test]
+fn main_returns_the_exit_code() {
+    assert_eq!(run_str("int main() { return 42; }"), 42);
+    assert_eq!(run_str("int main() { return 1; return 2; }"), 1);
+    assert_eq!(run_str("int main() { int x = 1; }"), 0);
+    assert_eq!(
+        run_str("int main() { int x = 5; if (x > 3) { return x * 2; } return 0; }"),
+        10
+    );
+}
+
+#[test]
+fn variables_and_arithmetic_run() {
+    assert_eq!(run_str("int main() { return 2 + 3 * 4 - 10 / 5 % 3; }"), 12);
+    assert_eq!(run_str("int main() { return (7 & 3) | 8 ^ 1 << 2; }"), 15);
+    assert_eq!(
+        run_str("int main() { int a = 3; int b = a * a; a = b - a; return a; }"),
+        6
+    );
+    assert_eq!(
+        run_str("int main() { int x = 1; if (true) { int y = 2; x = x + y; } int z = 4; return x + z; }"),
+        7
+    );
+    assert_eq!(
+        run_str("int main() { int x = 1; { int x = 5; x = x * 2; } { int y = 3; x = x + y; } return x; }"),
+        4
+    );
+    assert_eq!(
+        run_str("int main() { float f = 1.5 * 2.0; if (f == 3.0) { return 1; } return 0; }"),
+        1
+    );
+    assert_eq!(
+        run_str("int main() { int x = 10; x += 5; x -= 3; x *= 2; x /= 4; x %= 4; x <<= 3; x |= 1; return x; }"),
+        17
+    );
+    assert_eq!(
+        run_str("int main() { int sum = 0; for (int i = 1; i <= 4; i += 1) { sum += i; } return sum; }"),
+        10
+    );
+}
+
+#[test]
+fn sized_integers_run() {
+    assert_eq!(
+        run_str("int main() { i8 x = 127; x += 1; if (x == -128) { return 1; } return 0; }"),
+        1
+    );
+    assert_eq!(
+        run_str("int main() { u8 x = 250; x += 10; u8 y = 0; y -= 1; return int(x) + int(y); }"),
+        259
+    );
+    assert_eq!(
+        run_str("int main() { int m = -1; u32 x = u32(m); if (x > 1) { return int(x / 65536); } return 0; }"),
+        65535
+    );
+    assert_eq!(
+        run_str("int main() { i64 x = 1; x <<= 40; i64 y = x * 3; return int(y >> 38); }"),
+        12
+    );
+    assert_eq!(
+        run_str("int main() { i8 m = -2; u64 x = u64(m); if (x > 5) { return int(x % 7); } return 0; }"),
+        0
+    );
+    assert_eq!(
+        run_str("int main() { int x = 2147483647; x += 1; if (x < 0) { return 1; } return 0; }"),
+        1
+    );
+    assert_eq!(
+        run_str("i16 f(i16 a) { return a * 2; } int main() { return int(f(-20000)); }"),
+        25536
+    );
+}
+
+#[test]
+fn function_calls_run() {
+    assert_eq!(
+        run_str("int add(int a, int b) { return a + b; } int main() { return add(2, add(3, 4)); }"),
+        9
+    );
+    assert_eq!(
+        run_str("int main() { return fact(5); } int fact(int n) { if (n <= 1) { return 1; } return n * fact(n - 1); }"),
+        120
+    );
+    assert_eq!(
+        run_str("int f(int x) { int y = x * 2; return y; } int main() { int y = 1; int z = f(10); return y + z; }"),
+        21
+    );
 }
@@ -7,7 +7,19 @@ pub fn interpret_bytecode(bytecode: Bytecode) -> i32 {
 
 struct Interpreter {
     bytecode: Bytecode,
-    stack: Vec<i32>,
+    /// Every value is 64 bits wide, so that it can hold any integer type.
+    /// Narrower integers are kept wrapped into their type's range by `WrapSigned` and `WrapUnsigned`.
+    stack: Vec<i64>,
+    /// Local variables of every active frame.
+    /// Each frame's slots start at its `locals_base`.
+    locals: Vec<i64>,
+    frames: Vec<Frame>,
+}
+
+#[derive(Debug)]
+struct Frame {
+    return_address: usize,
+    locals_base: usize,
 }
 
 impl Interpreter {
@@ -15,9 +27,15 @@ impl Interpreter {
         Self {
             bytecode,
             stack: Vec::new(),
+            locals: Vec::new(),
+            frames: Vec::new(),
         }
     }
 
+    fn locals_base(&self) -> usize {
+        self.frames.last().map_or(0, |frame| frame.locals_base)
+    }
+
     fn run(&mut self) -> i32 {
 
 macro_rules! unwrap_or_return {
@@ -36,31 +54,152 @@ macro_rules! unwrap_or_return {
     };
 }
 
-        for instruction in self.bytecode.iter() {
+macro_rules! binary_op {
+    (|$lhs:ident, $rhs:ident| $result:expr) => {{
+        let $rhs = unwrap_or_return!(self.stack.pop());
+        let $lhs = unwrap_or_return!(self.stack.pop());
+        self.stack.push($result);
+    }};
+}
+
+macro_rules! float_binary_op {
+    (|$lhs:ident, $rhs:ident| $result:expr) => {
+        binary_op!(|$lhs, $rhs| {
+            let $lhs = f32::from_bits($lhs as u32);
+            let $rhs = f32::from_bits($rhs as u32);
+            $result
+        })
+    };
+}
+
+macro_rules! unsigned_binary_op {
+    (|$lhs:ident, $rhs:ident| $result:expr) => {
+        binary_op!(|$lhs, $rhs| {
+            let $lhs = $lhs as u64;
+            let $rhs = $rhs as u64;
+            $result
+        })
+    };
+}
+
+macro_rules! unary_op {
+    (|$operand:ident| $result:expr) => {{
+        let $operand = unwrap_or_return!(self.stack.pop());
+        self.stack.push($result);
+    }};
+}
+
+        let main = unwrap_or_return!(self.bytecode.function_by_name("main"));
+        let mut pc = main.address as usize;
+        self.frames.push(Frame {
+            return_address: pc,
+            locals_base: 0,
+        });
+
+        while let Some((instruction, size)) = self.bytecode.get(pc) {
+            pc += size;
             match instruction {
-                Instruction::Push(num) => self.stack.push(num),
+                Instruction::Push(num) => self.stack.push(num.into()),
+                Instruction::Const(index) => {
+                    // Constants are referred to by their index in the constant pool.
+                    unwrap_or_return!(self.bytecode.constant(index));
+                    self.stack.push(index.into());
+                }
                 Instruction::Pop => {
                     unwrap_or_return!(self.stack.pop());
                 }
-                Instruction::Add => {
-                    let a = unwrap_or_return!(self.stack.pop());
-                    let b = unwrap_or_return!(self.stack.pop());
-                    self.stack.push(a + b);
+                Instruction::Load(slot) => {
+                    let slot = self.locals_base() + slot as usize;
+                    let value = unwrap_or_return!(self.locals.get(slot));
+                    self.stack.push(*value);
+                }
+                Instruction::Store(slot) => {
+                    let value = unwrap_or_return!(self.stack.pop());
+                    let slot = self.locals_base() + slot as usize;
+                    if slot >= self.locals.len() {
+                        self.locals.resize(slot + 1, 0);
+                    }
+                    self.locals[slot] = value;
+                }
+                Instruction::Add => binary_op!(|a, b| a.wrapping_add(b)),
+                Instruction::Sub => binary_op!(|a, b| a.wrapping_sub(b)),
+                Instruction::Mul => binary_op!(|a, b| a.wrapping_mul(b)),
+                Instruction::Div => binary_op!(|a, b| unwrap_or_return!(a.checked_div(b))),
+                Instruction::Rem => binary_op!(|a, b| unwrap_or_return!(a.checked_rem(b))),
+                Instruction::Neg => unary_op!(|a| a.wrapping_neg()),
+                Instruction::Not => unary_op!(|a| (a == 0) as i64),
+                Instruction::BitNot => unary_op!(|a| !a),
+                Instruction::Eq => binary_op!(|a, b| (a == b) as i64),
+                Instruction::Ne => binary_op!(|a, b| (a != b) as i64),
+                Instruction::Lt => binary_op!(|a, b| (a < b) as i64),
+                Instruction::Le => binary_op!(|a, b| (a <= b) as i64),
+                Instruction::Gt => binary_op!(|a, b| (a > b) as i64),
+                Instruction::Ge => binary_op!(|a, b| (a >= b) as i64),
+                Instruction::BitAnd => binary_op!(|a, b| a & b),
+                Instruction::BitOr => binary_op!(|a, b| a | b),
+                Instruction::BitXor => binary_op!(|a, b| a ^ b),
+                Instruction::Shl => binary_op!(|a, b| a.wrapping_shl(b as u32)),
+                Instruction::Shr => binary_op!(|a, b| a.wrapping_shr(b as u32)),
+                Instruction::DivU => {
+                    unsigned_binary_op!(|a, b| unwrap_or_return!(a.checked_div(b)) as i64)
+                }
+                Instruction::RemU => {
+                    unsigned_binary_op!(|a, b| unwrap_or_return!(a.checked_rem(b)) as i64)
+                }
+                Instruction::LtU => unsigned_binary_op!(|a, b| (a < b) as i64),
+                Instruction::LeU => unsigned_binary_op!(|a, b| (a <= b) as i64),
+                Instruction::GtU => unsigned_binary_op!(|a, b| (a > b) as i64),
+                Instruction::GeU => unsigned_binary_op!(|a, b| (a >= b) as i64),
+                Instruction::ShrU => unsigned_binary_op!(|a, b| a.wrapping_shr(b as u32) as i64),
+                Instruction::WrapSigned(bits) => {
+                    unary_op!(|a| a.wrapping_shl(64 - bits).wrapping_shr(64 - bits))
+                }
+                Instruction::WrapUnsigned(bits) => {
+                    unary_op!(|a| a & (1i64.wrapping_shl(bits) - 1))
                 }
-                Instruction::Sub => {
-                    let a = unwrap_or_return!(self.stack.pop());
-                    let b = unwrap_or_return!(self.stack.pop());
-                    self.stack.push(a - b);
+                Instruction::FAdd => float_binary_op!(|a, b| (a + b).to_bits().into()),
+                Instruction::FSub => float_binary_op!(|a, b| (a - b).to_bits().into()),
+                Instruction::FMul => float_binary_op!(|a, b| (a * b).to_bits().into()),
+                Instruction::FDiv => float_binary_op!(|a, b| (a / b).to_bits().into()),
+                Instruction::FRem => float_binary_op!(|a, b| (a % b).to_bits().into()),
+                Instruction::FNeg => unary_op!(|a| (-f32::from_bits(a as u32)).to_bits().into()),
+                Instruction::FEq => float_binary_op!(|a, b| (a == b) as i64),
+                Instruction::FNe => float_binary_op!(|a, b| (a != b) as i64),
+                Instruction::FLt => float_binary_op!(|a, b| (a < b) as i64),
+                Instruction::FLe => float_binary_op!(|a, b| (a <= b) as i64),
+                Instruction::FGt => float_binary_op!(|a, b| (a > b) as i64),
+                Instruction::FGe => float_binary_op!(|a, b| (a >= b) as i64),
+                Instruction::Jump(target) => pc = target as usize,
+                Instruction::JumpIfFalse(target) => {
+                    if unwrap_or_return!(self.stack.pop()) == 0 {
+                        pc = target as usize;
+                    }
                 }
-                Instruction::Mul => {
-                    let a = unwrap_or_return!(self.stack.pop());
-                    let b = unwrap_or_return!(self.stack.pop());
-                    self.stack.push(a * b);
+                Instruction::Call(index) => {
+                    let function = unwrap_or_return!(self.bytecode.function(index));
+                    let arguments_start = unwrap_or_return!(self
+                        .stack
+                        .len()
+                        .checked_sub(function.parameter_count as usize));
+                    let locals_base = self.locals.len();
+                    self.locals.extend(self.stack.drain(arguments_start..));
+                    self.frames.push(Frame {
+                        return_address: pc,
+                        locals_base,
+                    });
+                    pc = function.address as usize;
                 }
-                Instruction::Div => {
-                    let a = unwrap_or_return!(self.stack.pop());
-                    let b = unwrap_or_return!(self.stack.pop());
-                    self.stack.push(a / b);
+                Instruction::Ret => {
+                    let value = unwrap_or_return!(self.stack.pop());
+                    let frame = unwrap_or_return!(self.frames.pop());
+                    if self.frames.is_empty() {
+                        // Returning from main ends the program.
+                        // `main` returns an `int`, so its value already fits.
+                        return value as i32;
+                    }
+                    self.locals.truncate(frame.locals_base);
+                    self.stack.push(value);
+                    pc = frame.return_address;
                 }
             }
         }
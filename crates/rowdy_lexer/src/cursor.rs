@@ -22,9 +22,10 @@ impl<'a> Cursor<'a> {
     pub fn next(&mut self) -> Option<(char, Location)> {
         let loc = self.current_loc;
         let next = self.chars.next();
-        if next.is_some() {
+        if let Some(c) = next {
             self.current_loc.col += 1;
-            self.current_loc.char_num += 1;
+            // Spans slice the source with this, so it counts bytes rather than chars.
+            self.current_loc.char_num += c.len_utf8();
             if let Some('\n') = next {
                 self.current_loc = Location {
                     col: 1,
@@ -29,49 +29,56 @@ impl<'a> Iterator for UnqualifiedTokenIter<'a> {
 pub struct TokenIter<'a> {
     cursor: Cursor<'a>,
     code: &'a str,
+    last_span: Option<Span>,
 }
 
 impl<'a> TokenIter<'a> {
     pub fn is_empty(&self) -> bool {
         self.clone().next().is_none()
     }
+
+    /// The span of the most recently returned token.
+    /// Useful for pointing at the end of the input once the tokens run out.
+    pub fn last_span(&self) -> Option<Span> {
+        self.last_span
+    }
 }
 
 impl<'a> Iterator for TokenIter<'a> {
     type Item = QualifiedToken;
 
     fn next(&mut self) -> Option<Self::Item> {
-        Some(qualify_token(next_token(&mut self.cursor)?, self.code))
+        let token = qualify_token(next_token(&mut self.cursor)?, self.code);
+        self.last_span = Some(token.span);
+        Some(token)
     }
 }
 
-pub fn tokenize(compiler: &Compiler) -> TokenIter {
+pub fn tokenize(compiler: &Compiler) -> TokenIter<'_> {
     tokenize_str(&compiler.code)
 }
 
-pub fn tokenize_str(input: &str) -> TokenIter {
+pub fn tokenize_str(input: &str) -> TokenIter<'_> {
     let cursor = Cursor::new(input);
     TokenIter {
         cursor,
         code: input,
+        last_span: None,
     }
 }
 
-pub fn tokenize_unqualified(compiler: &Compiler) -> UnqualifiedTokenIter {
+pub fn tokenize_unqualified(compiler: &Compiler) -> UnqualifiedTokenIter<'_> {
     tokenize_str_unqualified(&compiler.code)
 }
 
-pub fn tokenize_str_unqualified(input: &str) -> UnqualifiedTokenIter {
+pub fn tokenize_str_unqualified(input: &str) -> UnqualifiedTokenIter<'_> {
     let cursor = Cursor::new(input);
     UnqualifiedTokenIter { cursor }
 }
 
 fn next_token(cursor: &mut Cursor) -> Option<Token> {
     loop {
-        let (next, start_loc) = match cursor.next() {
-            Some(result) => result,
-            None => return None,
-        };
+        let (next, start_loc) = cursor.next()?;
         break Some(match next {
             ';' => Token {
                 typ: TokenType::End,
@@ -105,6 +112,10 @@ fn next_token(cursor: &mut Cursor) -> Option<Token> {
                 typ: TokenType::SpecialChar(SpecialChar::Comma),
                 span: Span::from_loc(start_loc),
             },
+            '#' => Token {
+                typ: TokenType::SpecialChar(SpecialChar::Hash),
+                span: Span::from_loc(start_loc),
+            },
             '=' => match cursor.peek(0) {
                 Some(('=', end_loc)) => {
                     cursor.consume(0);
@@ -158,15 +169,206 @@ fn next_token(cursor: &mut Cursor) -> Option<Token> {
                     span: Span::from_loc(start_loc),
                 },
             },
-            c if c.is_ascii_digit() => cursor.number(start_loc),
+            '*' => match cursor.peek(0) {
+                Some(('=', end_loc)) => {
+                    cursor.consume(0);
+                    Token {
+                        typ: TokenType::Operator(Operator::MulAssign),
+                        span: Span::from_start_end(start_loc, end_loc),
+                    }
+                }
+                _ => Token {
+                    typ: TokenType::Operator(Operator::Mul),
+                    span: Span::from_loc(start_loc),
+                },
+            },
+            '/' => match cursor.peek(0) {
+                Some(('/', _)) => {
+                    cursor.eat_while(|c| *c != '\n');
+                    continue;
+                }
+                Some(('*', end_loc)) => {
+                    cursor.consume(0);
+                    if cursor.block_comment() {
+                        continue;
+                    }
+                    Token {
+                        typ: TokenType::Error(LexError::UnterminatedBlockComment),
+                        span: Span::from_start_end(start_loc, end_loc),
+                    }
+                }
+                Some(('=', end_loc)) => {
+                    cursor.consume(0);
+                    Token {
+                        typ: TokenType::Operator(Operator::DivAssign),
+                        span: Span::from_start_end(start_loc, end_loc),
+                    }
+                }
+                _ => Token {
+                    typ: TokenType::Operator(Operator::Div),
+                    span: Span::from_loc(start_loc),
+                },
+            },
+            '%' => match cursor.peek(0) {
+                Some(('=', end_loc)) => {
+                    cursor.consume(0);
+                    Token {
+                        typ: TokenType::Operator(Operator::RemAssign),
+                        span: Span::from_start_end(start_loc, end_loc),
+                    }
+                }
+                _ => Token {
+                    typ: TokenType::Operator(Operator::Rem),
+                    span: Span::from_loc(start_loc),
+                },
+            },
+            '^' => match cursor.peek(0) {
+                Some(('=', end_loc)) => {
+                    cursor.consume(0);
+                    Token {
+                        typ: TokenType::Operator(Operator::BitXorAssign),
+                        span: Span::from_start_end(start_loc, end_loc),
+                    }
+                }
+                _ => Token {
+                    typ: TokenType::Operator(Operator::BitXor),
+                    span: Span::from_loc(start_loc),
+                },
+            },
+            '~' => Token {
+                typ: TokenType::Operator(Operator::BitNot),
+                span: Span::from_loc(start_loc),
+            },
+            '!' => match cursor.peek(0) {
+                Some(('=', end_loc)) => {
+                    cursor.consume(0);
+                    Token {
+                        typ: TokenType::Operator(Operator::NotEquals),
+                        span: Span::from_start_end(start_loc, end_loc),
+                    }
+                }
+                _ => Token {
+                    typ: TokenType::Operator(Operator::Not),
+                    span: Span::from_loc(start_loc),
+                },
+            },
+            '<' => match cursor.peek(0) {
+                Some(('<', end_loc)) => {
+                    cursor.consume(0);
+                    match cursor.peek(0) {
+                        Some(('=', end_loc)) => {
+                            cursor.consume(0);
+                            Token {
+                                typ: TokenType::Operator(Operator::ShiftLeftAssign),
+                                span: Span::from_start_end(start_loc, end_loc),
+                            }
+                        }
+                        _ => Token {
+                            typ: TokenType::Operator(Operator::ShiftLeft),
+                            span: Span::from_start_end(start_loc, end_loc),
+                        },
+                    }
+                }
+                Some(('=', end_loc)) => {
+                    cursor.consume(0);
+                    Token {
+                        typ: TokenType::Operator(Operator::LessEquals),
+                        span: Span::from_start_end(start_loc, end_loc),
+                    }
+                }
+                _ => Token {
+                    typ: TokenType::Operator(Operator::Less),
+                    span: Span::from_loc(start_loc),
+                },
+            },
+            '>' => match cursor.peek(0) {
+                Some(('>', end_loc)) => {
+                    cursor.consume(0);
+                    match cursor.peek(0) {
+                        Some(('=', end_loc)) => {
+                            cursor.consume(0);
+                            Token {
+                                typ: TokenType::Operator(Operator::ShiftRightAssign),
+                                span: Span::from_start_end(start_loc, end_loc),
+                            }
+                        }
+                        _ => Token {
+                            typ: TokenType::Operator(Operator::ShiftRight),
+                            span: Span::from_start_end(start_loc, end_loc),
+                        },
+                    }
+                }
+                Some(('=', end_loc)) => {
+                    cursor.consume(0);
+                    Token {
+                        typ: TokenType::Operator(Operator::GreaterEquals),
+                        span: Span::from_start_end(start_loc, end_loc),
+                    }
+                }
+                _ => Token {
+                    typ: TokenType::Operator(Operator::Greater),
+                    span: Span::from_loc(start_loc),
+                },
+            },
+            '&' => match cursor.peek(0) {
+                Some(('&', end_loc)) => {
+                    cursor.consume(0);
+                    Token {
+                        typ: TokenType::Operator(Operator::And),
+                        span: Span::from_start_end(start_loc, end_loc),
+                    }
+                }
+                Some(('=', end_loc)) => {
+                    cursor.consume(0);
+                    Token {
+                        typ: TokenType::Operator(Operator::BitAndAssign),
+                        span: Span::from_start_end(start_loc, end_loc),
+                    }
+                }
+                _ => Token {
+                    typ: TokenType::Operator(Operator::BitAnd),
+                    span: Span::from_loc(start_loc),
+                },
+            },
+            '|' => match cursor.peek(0) {
+                Some(('|', end_loc)) => {
+                    cursor.consume(0);
+                    Token {
+                        typ: TokenType::Operator(Operator::Or),
+                        span: Span::from_start_end(start_loc, end_loc),
+                    }
+                }
+                Some(('=', end_loc)) => {
+                    cursor.consume(0);
+                    Token {
+                        typ: TokenType::Operator(Operator::BitOrAssign),
+                        span: Span::from_start_end(start_loc, end_loc),
+                    }
+                }
+                _ => Token {
+                    typ: TokenType::Operator(Operator::BitOr),
+                    span: Span::from_loc(start_loc),
+                },
+            },
+            '"' => cursor.quoted('"', start_loc),
+            '\'' => cursor.quoted('\'', start_loc),
+            c if c.is_ascii_digit() => cursor.number(c, start_loc),
             c if is_symbol_start(&c) => cursor.symbol(start_loc),
             c if c.is_whitespace() => continue,
 
-            c => todo!("'{}'", c),
+            c => Token {
+                typ: TokenType::Error(LexError::UnexpectedChar(c)),
+                span: Span::from_loc(start_loc),
+            },
         });
     }
 }
 
+#[inline]
+fn is_digit_or_separator(c: &char) -> bool {
+    matches!(c, '0'..='9' | '_')
+}
+
 #[inline]
 fn is_symbol_start(c: &char) -> bool {
     matches!(c, 'a'..='z'|'A'..='Z'|'_')
@@ -178,19 +380,103 @@ fn is_symbol_middle(c: &char) -> bool {
 }
 
 impl<'a> Cursor<'a> {
-    fn number(&mut self, start_loc: Location) -> Token {
-        let end_loc = self.eat_while(char::is_ascii_digit);
-        if let Some('.') = self.peek_char(0) {
-            self.consume(0);
-            let end_loc = self.eat_while(char::is_ascii_digit);
-            Token {
-                typ: TokenType::FloatLit,
-                span: Span::from_start_end(start_loc, end_loc.unwrap_or(start_loc)),
-            }
+    /// Reads a number literal whose first digit has already been consumed.
+    /// The digits are only checked once the token is qualified.
+    fn number(&mut self, first: char, start_loc: Location) -> Token {
+        let mut end_loc = start_loc;
+        let mut float = false;
+        if first == '0' && matches!(self.peek_char(0), Some('x' | 'o' | 'b')) {
+            // Letters are digits in hex, so the suffix can't be told apart from the digits here.
+            end_loc = self.eat_while(is_symbol_middle).unwrap_or(end_loc);
         } else {
-            Token {
-                typ: TokenType::IntLit,
-                span: Span::from_start_end(start_loc, end_loc.unwrap_or(start_loc)),
+            end_loc = self.eat_while(is_digit_or_separator).unwrap_or(end_loc);
+            if let Some(('.', dot_loc)) = self.peek(0) {
+                self.consume(0);
+                end_loc = self.eat_while(is_digit_or_separator).unwrap_or(dot_loc);
+                float = true;
+            }
+            let exponent = match (self.peek_char(0), self.peek_char(1), self.peek_char(2)) {
+                (Some('e' | 'E'), Some(digit), _) if digit.is_ascii_digit() => Some(1),
+                (Some('e' | 'E'), Some('+' | '-'), Some(digit)) if digit.is_ascii_digit() => {
+                    Some(2)
+                }
+                _ => None,
+            };
+            if let Some(n) = exponent {
+                self.consume(n - 1);
+                end_loc = self.eat_while(is_digit_or_separator).unwrap_or(end_loc);
+                float = true;
+            }
+            // A lone `f` suffix makes any number a float.
+            if self.peek_char(0) == Some('f')
+                && !self.peek_char(1).is_some_and(|c| is_symbol_middle(&c))
+            {
+                float = true;
+            }
+            end_loc = self.eat_while(is_symbol_middle).unwrap_or(end_loc);
+        }
+        Token {
+            typ: if float {
+                TokenType::FloatLit
+            } else {
+                TokenType::IntLit
+            },
+            span: Span::from_start_end(start_loc, end_loc),
+        }
+    }
+
+    /// Skips the rest of a block comment whose opening `/*` has already been consumed.
+    /// Block comments nest, so every `/*` inside needs its own `*/`.
+    /// Returns false if the input ends before the comment is closed.
+    fn block_comment(&mut self) -> bool {
+        let mut depth = 1usize;
+        while let Some((c, _)) = self.next() {
+            match (c, self.peek_char(0)) {
+                ('/', Some('*')) => {
+                    self.consume(0);
+                    depth += 1;
+                }
+                ('*', Some('/')) => {
+                    self.consume(0);
+                    depth -= 1;
+                    if depth == 0 {
+                        return true;
+                    }
+                }
+                _ => {}
+            }
+        }
+        false
+    }
+
+    /// Reads the rest of a string or character literal after its opening quote.
+    /// The escapes are only skipped over here and get interpreted once the token is qualified.
+    fn quoted(&mut self, quote: char, start_loc: Location) -> Token {
+        let (literal, unterminated) = match quote {
+            '"' => (TokenType::StringLit, LexError::UnterminatedString),
+            _ => (TokenType::CharLit, LexError::UnterminatedChar),
+        };
+        let mut end_loc = start_loc;
+        loop {
+            match self.peek_char(0) {
+                None | Some('\n') => {
+                    return Token {
+                        typ: TokenType::Error(unterminated),
+                        span: Span::from_start_end(start_loc, end_loc),
+                    }
+                }
+                Some(c) => {
+                    end_loc = self.consume(0).expect("peeked a char").1;
+                    if c == quote {
+                        return Token {
+                            typ: literal,
+                            span: Span::from_start_end(start_loc, end_loc),
+                        };
+                    }
+                    if c == '\\' && !matches!(self.peek_char(0), None | Some('\n')) {
+                        self.consume(0);
+                    }
+                }
             }
         }
     }
@@ -203,3 +489,105 @@ impl<'a> Cursor<'a> {
         }
     }
 }
+
+#[test]
+fn comments() {
+    let types: Vec<_> = tokenize_str("a // b\n/* c /* d */ e */ f / g /* h")
+        .map(|token| token.typ)
+        .collect();
+    assert_eq!(
+        types,
+        [
+            QualifiedTokenType::Symbol("a".into()),
+            QualifiedTokenType::Symbol("f".into()),
+            QualifiedTokenType::Operator(Operator::Div),
+            QualifiedTokenType::Symbol("g".into()),
+            QualifiedTokenType::Error(LexError::UnterminatedBlockComment),
+        ]
+    );
+}
+
+#[test]
+fn lexer_errors() {
+    let types: Vec<_> = tokenize_str("a @ 2147483647 2147483648 $")
+        .map(|token| token.typ)
+        .collect();
+    assert_eq!(
+        types,
+        [
+            QualifiedTokenType::Symbol("a".into()),
+            QualifiedTokenType::Error(LexError::UnexpectedChar('@')),
+            QualifiedTokenType::IntLit(i32::MAX),
+            QualifiedTokenType::Error(LexError::IntOutOfRange),
+            QualifiedTokenType::Error(LexError::UnexpectedChar('$')),
+        ]
+    );
+}
+
+#[test]
+fn maximal_munch() {
+    let ops: Vec<_> = tokenize_str("<<= << <= < >>= >> >= > &&= &= & ||| |= *= /= %= ^= !== ~")
+        .map(|token| match token.typ {
+            QualifiedTokenType::Operator(op) => op.as_str(),
+            other => panic!("expected an operator, found {other}"),
+        })
+        .collect();
+    assert_eq!(
+        ops,
+        [
+            "<<=", "<<", "<=", "<", ">>=", ">>", ">=", ">", "&&", "=", "&=", "&", "||", "|", "|=",
+            "*=", "/=", "%=", "^=", "!=", "=", "~"
+        ]
+    );
+}
+
+#[test]
+fn string_and_char_literals() {
+    let types: Vec<_> =
+        tokenize_str(r#""a\tb\"\\\u{e9}" 'x' '\n' '\'' "é" "\q" 'ab' '' "open"#)
+            .map(|token| token.typ)
+            .collect();
+    assert_eq!(
+        types,
+        [
+            QualifiedTokenType::StringLit("a\tb\"\\é".into()),
+            QualifiedTokenType::CharLit('x'),
+            QualifiedTokenType::CharLit('\n'),
+            QualifiedTokenType::CharLit('\''),
+            QualifiedTokenType::StringLit("é".into()),
+            QualifiedTokenType::Error(LexError::InvalidEscape),
+            QualifiedTokenType::Error(LexError::CharLitLength),
+            QualifiedTokenType::Error(LexError::CharLitLength),
+            QualifiedTokenType::Error(LexError::UnterminatedString),
+        ]
+    );
+}
+
+#[test]
+fn number_literals() {
+    let types: Vec<_> = tokenize_str(
+        "0xFF 0o17 0b1010 1_000_000 1e9 2.5e-3 3.0f 2f 1. 0x 0b102 12ab 1.5x 1e39 0x8000_0000",
+    )
+    .map(|token| token.typ)
+    .collect();
+    assert_eq!(
+        types,
+        [
+            QualifiedTokenType::IntLit(255),
+            QualifiedTokenType::IntLit(15),
+            QualifiedTokenType::IntLit(10),
+            QualifiedTokenType::IntLit(1_000_000),
+            QualifiedTokenType::FloatLit(1e9),
+            QualifiedTokenType::FloatLit(2.5e-3),
+            QualifiedTokenType::FloatLit(3.0),
+            QualifiedTokenType::FloatLit(2.0),
+            QualifiedTokenType::FloatLit(1.0),
+            QualifiedTokenType::Error(LexError::NoDigits),
+            QualifiedTokenType::Error(LexError::InvalidDigit { radix: 2 }),
+            QualifiedTokenType::Error(LexError::InvalidSuffix("ab".into())),
+            QualifiedTokenType::Error(LexError::InvalidSuffix("x".into())),
+            QualifiedTokenType::Error(LexError::FloatOutOfRange),
+            QualifiedTokenType::Error(LexError::IntOutOfRange),
+        ]
+    );
+}
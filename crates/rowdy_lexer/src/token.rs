@@ -14,7 +14,11 @@ pub enum TokenType {
     SpecialChar(SpecialChar),
     IntLit,
     FloatLit,
+    StringLit,
+    CharLit,
     End,
+    /// Input which could not be turned into a token.
+    Error(LexError),
 }
 
 impl fmt::Display for TokenType {
@@ -25,11 +29,40 @@ impl fmt::Display for TokenType {
             Self::SpecialChar(_) => write!(f, "special char"),
             Self::IntLit => write!(f, "int lit"),
             Self::FloatLit => write!(f, "float lit"),
+            Self::StringLit => write!(f, "string lit"),
+            Self::CharLit => write!(f, "char lit"),
             Self::End => write!(f, "end"),
+            Self::Error(_) => write!(f, "error"),
         }
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LexError {
+    /// A `/*` without a matching `*/`.
+    UnterminatedBlockComment,
+    /// A character which cannot start any token.
+    UnexpectedChar(char),
+    /// An integer literal too large to fit in an `int`.
+    IntOutOfRange,
+    /// A float literal too large to fit in a `float`.
+    FloatOutOfRange,
+    /// A digit which doesn't exist in the base of the literal it's in, like the `2` in `0b102`.
+    InvalidDigit { radix: u32 },
+    /// A base prefix like `0x` without any digits after it.
+    NoDigits,
+    /// Letters after a number literal which don't make up a known suffix.
+    InvalidSuffix(String),
+    /// A string literal which reaches the end of the line before its closing `"`.
+    UnterminatedString,
+    /// A character literal which reaches the end of the line before its closing `'`.
+    UnterminatedChar,
+    /// A `\` in a string or character literal which isn't followed by a known escape.
+    InvalidEscape,
+    /// A character literal which doesn't hold exactly one character.
+    CharLitLength,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Operator {
     Plus,
@@ -40,6 +73,71 @@ pub enum Operator {
     Decrement,
     Assign,
     Equals,
+    NotEquals,
+    Mul,
+    MulAssign,
+    Div,
+    DivAssign,
+    Rem,
+    RemAssign,
+    Less,
+    LessEquals,
+    Greater,
+    GreaterEquals,
+    Not,
+    And,
+    Or,
+    BitAnd,
+    BitAndAssign,
+    BitOr,
+    BitOrAssign,
+    BitXor,
+    BitXorAssign,
+    BitNot,
+    ShiftLeft,
+    ShiftLeftAssign,
+    ShiftRight,
+    ShiftRightAssign,
+}
+
+impl Operator {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Plus => "+",
+            Self::PlusAssign => "+=",
+            Self::Increment => "++",
+            Self::Sub => "-",
+            Self::SubAssign => "-=",
+            Self::Decrement => "--",
+            Self::Assign => "=",
+            Self::Equals => "==",
+            Self::NotEquals => "!=",
+            Self::Mul => "*",
+            Self::MulAssign => "*=",
+            Self::Div => "/",
+            Self::DivAssign => "/=",
+            Self::Rem => "%",
+            Self::RemAssign => "%=",
+            Self::Less => "<",
+            Self::LessEquals => "<=",
+            Self::Greater => ">",
+            Self::GreaterEquals => ">=",
+            Self::Not => "!",
+            Self::And => "&&",
+            Self::Or => "||",
+            Self::BitAnd => "&",
+            Self::BitAndAssign => "&=",
+            Self::BitOr => "|",
+            Self::BitOrAssign => "|=",
+            Self::BitXor => "^",
+            Self::BitXorAssign => "^=",
+            Self::BitNot => "~",
+            Self::ShiftLeft => "<<",
+            Self::ShiftLeftAssign => "<<=",
+            Self::ShiftRight => ">>",
+            Self::ShiftRightAssign => ">>=",
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -51,6 +149,22 @@ pub enum SpecialChar {
     LBracket,
     RBracket,
     Comma,
+    Hash,
+}
+
+impl SpecialChar {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::LParen => "(",
+            Self::RParen => ")",
+            Self::LBrace => "{",
+            Self::RBrace => "}",
+            Self::LBracket => "[",
+            Self::RBracket => "]",
+            Self::Comma => ",",
+            Self::Hash => "#",
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -60,6 +174,22 @@ pub enum Keyword {
     While,
     For,
     Return,
+    True,
+    False,
+}
+
+impl Keyword {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::If => "if",
+            Self::Else => "else",
+            Self::While => "while",
+            Self::For => "for",
+            Self::Return => "return",
+            Self::True => "true",
+            Self::False => "false",
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -69,8 +199,28 @@ pub enum QualifiedTokenType {
     SpecialChar(SpecialChar),
     IntLit(i32),
     FloatLit(f32),
+    StringLit(String),
+    CharLit(char),
     Keyword(Keyword),
     End,
+    Error(LexError),
+}
+
+impl fmt::Display for QualifiedTokenType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Symbol(symbol) => write!(f, "identifier `{symbol}`"),
+            Self::Operator(op) => write!(f, "`{}`", op.as_str()),
+            Self::SpecialChar(c) => write!(f, "`{}`", c.as_str()),
+            Self::IntLit(value) => write!(f, "integer literal `{value}`"),
+            Self::FloatLit(value) => write!(f, "float literal `{value}`"),
+            Self::StringLit(value) => write!(f, "string literal `{value:?}`"),
+            Self::CharLit(value) => write!(f, "character literal `{value:?}`"),
+            Self::Keyword(keyword) => write!(f, "keyword `{}`", keyword.as_str()),
+            Self::End => write!(f, "`;`"),
+            Self::Error(_) => write!(f, "invalid token"),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -104,6 +254,14 @@ pub fn qualify_token(token: Token, code: &str) -> QualifiedToken {
                     typ: QualifiedTokenType::Keyword(Keyword::Return),
                     span: token.span,
                 },
+                "true" => QualifiedToken {
+                    typ: QualifiedTokenType::Keyword(Keyword::True),
+                    span: token.span,
+                },
+                "false" => QualifiedToken {
+                    typ: QualifiedTokenType::Keyword(Keyword::False),
+                    span: token.span,
+                },
                 _ => QualifiedToken {
                     typ: QualifiedTokenType::Symbol(symbol.to_string()),
                     span: token.span,
@@ -119,28 +277,137 @@ pub fn qualify_token(token: Token, code: &str) -> QualifiedToken {
             span: token.span,
         },
         TokenType::IntLit => QualifiedToken {
-            typ: QualifiedTokenType::IntLit(
-                token
-                    .span
-                    .slice(code)
-                    .parse()
-                    .expect("Failed to parse int lit"),
-            ),
+            typ: match int_value(token.span.slice(code)) {
+                Ok(value) => QualifiedTokenType::IntLit(value),
+                Err(error) => QualifiedTokenType::Error(error),
+            },
             span: token.span,
         },
         TokenType::FloatLit => QualifiedToken {
-            typ: QualifiedTokenType::FloatLit(
-                token
-                    .span
-                    .slice(code)
-                    .parse()
-                    .expect("Failed to parse float lit"),
-            ),
+            typ: match float_value(token.span.slice(code)) {
+                Ok(value) => QualifiedTokenType::FloatLit(value),
+                Err(error) => QualifiedTokenType::Error(error),
+            },
+            span: token.span,
+        },
+        TokenType::StringLit => QualifiedToken {
+            typ: match unescape(token.span.slice(code)) {
+                Ok(value) => QualifiedTokenType::StringLit(value),
+                Err(error) => QualifiedTokenType::Error(error),
+            },
+            span: token.span,
+        },
+        TokenType::CharLit => QualifiedToken {
+            typ: match unescape(token.span.slice(code)) {
+                Ok(value) => {
+                    let mut chars = value.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(c), None) => QualifiedTokenType::CharLit(c),
+                        _ => QualifiedTokenType::Error(LexError::CharLitLength),
+                    }
+                }
+                Err(error) => QualifiedTokenType::Error(error),
+            },
             span: token.span,
         },
         TokenType::End => QualifiedToken {
             typ: QualifiedTokenType::End,
             span: token.span,
         },
+        TokenType::Error(error) => QualifiedToken {
+            typ: QualifiedTokenType::Error(error),
+            span: token.span,
+        },
+    }
+}
+
+/// Works out the value of an integer literal such as `42`, `1_000`, `0xFF`, `0o17` or `0b1010`.
+fn int_value(literal: &str) -> Result<i32, LexError> {
+    let (radix, digits) = match literal.get(..2) {
+        Some("0x") => (16, &literal[2..]),
+        Some("0o") => (8, &literal[2..]),
+        Some("0b") => (2, &literal[2..]),
+        _ => (10, literal),
+    };
+    if radix == 10 {
+        let suffix_start = digits
+            .find(|c: char| !matches!(c, '0'..='9' | '_'))
+            .unwrap_or(digits.len());
+        if suffix_start != digits.len() {
+            return Err(LexError::InvalidSuffix(digits[suffix_start..].to_owned()));
+        }
+    }
+
+    let digits: String = digits.chars().filter(|c| *c != '_').collect();
+    if digits.is_empty() {
+        return Err(LexError::NoDigits);
+    }
+    if !digits.chars().all(|c| c.is_digit(radix)) {
+        return Err(LexError::InvalidDigit { radix });
+    }
+    // Every digit is valid, so the only way for this to fail is overflow.
+    i32::from_str_radix(&digits, radix).map_err(|_| LexError::IntOutOfRange)
+}
+
+/// Works out the value of a float literal such as `1.5`, `1e9`, `2.5e-3` or `3.0f`.
+fn float_value(literal: &str) -> Result<f32, LexError> {
+    let suffix_start = literal
+        .rfind(|c: char| matches!(c, '0'..='9' | '.' | '_'))
+        .map_or(0, |i| i + 1);
+    match &literal[suffix_start..] {
+        "" | "f" => {}
+        suffix => return Err(LexError::InvalidSuffix(suffix.to_owned())),
+    }
+
+    let number: String = literal[..suffix_start]
+        .chars()
+        .filter(|c| *c != '_')
+        .collect();
+    match number.parse::<f32>() {
+        Ok(value) if value.is_infinite() => Err(LexError::FloatOutOfRange),
+        Ok(value) => Ok(value),
+        Err(_) => Err(LexError::InvalidDigit { radix: 10 }),
+    }
+}
+
+/// Strips the quotes off of a string or character literal and replaces its escape sequences
+/// with the characters they stand for.
+fn unescape(literal: &str) -> Result<String, LexError> {
+    let mut chars = literal[1..literal.len() - 1].chars();
+    let mut value = String::new();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        value.push(match chars.next() {
+            Some('n') => '\n',
+            Some('t') => '\t',
+            Some('r') => '\r',
+            Some('0') => '\0',
+            Some('\\') => '\\',
+            Some('"') => '"',
+            Some('\'') => '\'',
+            Some('u') => {
+                if chars.next() != Some('{') {
+                    return Err(LexError::InvalidEscape);
+                }
+                let mut digits = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => digits.push(c),
+                        None => return Err(LexError::InvalidEscape),
+                    }
+                }
+                u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .filter(|_| (1..=6).contains(&digits.len()))
+                    .and_then(char::from_u32)
+                    .ok_or(LexError::InvalidEscape)?
+            }
+            _ => return Err(LexError::InvalidEscape),
+        });
     }
+    Ok(value)
 }
@@ -31,7 +31,7 @@ pub struct SourceLocation {
     pub loc: Location,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Span {
     // pub file: Source<'a>,
     pub start: Location,
@@ -49,7 +49,7 @@ impl Location {
 
 impl PartialOrd for Location {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.char_num.partial_cmp(&other.char_num)
+        Some(self.cmp(other))
     }
 }
 
@@ -136,7 +136,7 @@ impl Span {
         unsafe {
             let start = start.as_ptr().offset_from(s.as_ptr()) as _;
             let end = end.as_ptr().add(end.len()).offset_from(s.as_ptr()) as _;
-            &s[start..=end]
+            &s[start..end]
         }
     }
 
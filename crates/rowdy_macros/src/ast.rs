@@ -128,14 +128,53 @@ pub fn ast(
                 }
             };
 
+            // Variants can be restricted to some of the modules, the same way other items can.
+            let mut variant_annotations = Vec::new();
+            for variant in item_enum.variants.iter_mut() {
+                let attrs = extract_ast_attrs(&mut variant.attrs);
+                match ItemAnnotations::new(attrs) {
+                    Ok(ItemAnnotations::All) => variant_annotations.push(None),
+                    Ok(ItemAnnotations::Some(mods)) => {
+                        for mod_name in &mods {
+                            if !new_mods.contains_key(&mod_name.to_string()) {
+                                errors.push(syn::Error::new_spanned(
+                                    mod_name,
+                                    "module not specified in original macro invocation or the base module",
+                                ));
+                            }
+                        }
+                        variant_annotations.push(Some(
+                            mods.iter()
+                                .map(|ident| ident.to_string())
+                                .collect::<Vec<_>>(),
+                        ));
+                    }
+                    Err(errs) => errors.extend(errs),
+                }
+            }
+            let enum_for_module = |module: &str| {
+                let mut item_enum = item_enum.clone();
+                item_enum.variants = item_enum
+                    .variants
+                    .into_iter()
+                    .zip(&variant_annotations)
+                    .filter(|(_, mods)| match mods {
+                        Some(mods) => mods.iter().any(|name| name == module),
+                        None => true,
+                    })
+                    .map(|(variant, _)| variant)
+                    .collect();
+                item_enum
+            };
+
             if struct_annotation.annotations.is_empty() {
-                new_mods.values_mut().for_each(|modd| {
-                    add_content(modd, item_enum.clone());
+                new_mods.iter_mut().for_each(|(name, modd)| {
+                    add_content(modd, enum_for_module(name));
                 });
             } else {
                 add_content(
                     new_mods.get_mut(&base_ident.to_string()).unwrap(),
-                    item_enum.clone(),
+                    enum_for_module(&base_ident.to_string()),
                 );
                 for annotation in struct_annotation.annotations {
                     match new_mods.get_mut(&annotation.module.to_string()) {
@@ -180,7 +219,7 @@ pub fn ast(
                             };
                             add_content(modd, new_deref_mut);
 
-                            let mut inner = item_enum.clone();
+                            let mut inner = enum_for_module(&annotation.module.to_string());
                             inner.ident = inner_enum;
 
                             add_content(modd, inner);
@@ -331,12 +370,8 @@ impl StructAnnotation {
                             "module name cannot have arguments",
                         ));
                     }
-                    match segments.next() {
-                        Some(other) => {
-                            errors
-                                .push(syn::Error::new_spanned(other, "Max 2 parts of ast marker"));
-                        }
-                        None => {}
+                    if let Some(other) = segments.next() {
+                        errors.push(syn::Error::new_spanned(other, "Max 2 parts of ast marker"));
                     }
                     // annotation
                     match syn::parse2::<syn::FieldsNamed>(attr.tokens) {
@@ -398,31 +433,24 @@ impl ItemAnnotations {
                 }
             }
 
-            match segments.next() {
-                Some(mod_name) => {
-                    if !mod_name.arguments.is_none() {
-                        errors.push(syn::Error::new(
-                            mod_name.arguments.span(),
-                            "module name cannot have arguments",
-                        ));
-                    }
-                    match segments.next() {
-                        Some(other) => {
-                            errors
-                                .push(syn::Error::new_spanned(other, "Max 2 parts of ast marker"));
-                        }
-                        None => {}
-                    }
-                    if attr.tokens.is_empty() {
-                        modules.push(mod_name.ident.clone())
-                    } else {
-                        errors.push(syn::Error::new_spanned(
-                            attr.tokens,
-                            "Only structs can have additional information here",
-                        ))
-                    }
+            if let Some(mod_name) = segments.next() {
+                if !mod_name.arguments.is_none() {
+                    errors.push(syn::Error::new(
+                        mod_name.arguments.span(),
+                        "module name cannot have arguments",
+                    ));
+                }
+                if let Some(other) = segments.next() {
+                    errors.push(syn::Error::new_spanned(other, "Max 2 parts of ast marker"));
+                }
+                if attr.tokens.is_empty() {
+                    modules.push(mod_name.ident.clone())
+                } else {
+                    errors.push(syn::Error::new_spanned(
+                        attr.tokens,
+                        "Only structs can have additional information here",
+                    ))
                 }
-                None => {}
             }
         }
         if errors.is_empty() {
@@ -100,7 +100,7 @@ fn as_bytes(input: &EnumInput) -> TokenStream {
     let match_arms = input.variants.iter().map(|variant| {
         let variant_name = &variant.ident;
         let fields = handle_fields(&variant.fields);
-        let arm_body = make_arm_body(&variant_name, &fields);
+        let arm_body = make_arm_body(variant_name, &fields);
         quote! {Self::#variant_name #fields => {#arm_body}}
     });
 
@@ -116,7 +116,6 @@ fn as_bytes(input: &EnumInput) -> TokenStream {
             }
         }
     }
-    .into()
 }
 
 enum HandledFields {
@@ -236,13 +235,13 @@ fn from_bytes(input: &EnumInput) -> TokenStream {
             let handled_fields = handle_fields(&variant.fields);
             let variant_name = &variant.ident;
 
-            // *(&bytes[1..5] as *const [u8] as *const i32)
+            // read_unaligned(bytes[1..5].as_ptr() as *const i32)
 
             match handled_fields {
                 HandledFields::Named { fields } => {
                     let mut byte = quote! {1};
                     let parsed_fields = fields.into_iter().map(|(name, ty)| {
-                        let result = quote! { #name: *(&bytes[(#byte)..(#byte + ::core::mem::size_of::<#ty>())] as *const _ as *const #ty) };
+                        let result = quote! { #name: ::core::ptr::read_unaligned(bytes[(#byte)..(#byte + ::core::mem::size_of::<#ty>())].as_ptr() as *const #ty) };
                         byte = quote! {#byte + ::core::mem::size_of::<#ty>()};
                         result
                     });
@@ -253,7 +252,7 @@ fn from_bytes(input: &EnumInput) -> TokenStream {
                 HandledFields::Unnamed { fields } => {
                     let mut byte = quote! {1};
                     let parsed_fields = fields.into_iter().map(|(_, ty)| {
-                        let result = quote! { *(&bytes[(#byte)..(#byte) + ::core::mem::size_of::<#ty>()] as *const [u8] as *const #ty) };
+                        let result = quote! { ::core::ptr::read_unaligned(bytes[(#byte)..(#byte) + ::core::mem::size_of::<#ty>()].as_ptr() as *const #ty) };
                         byte = quote!{(#byte) + ::core::mem::size_of::<#ty>()};
                         result
                     });
@@ -280,5 +279,4 @@ fn from_bytes(input: &EnumInput) -> TokenStream {
             }
         }
     }
-    .into()
 }
@@ -1,29 +1,217 @@
 use rowdy_ast::{base::*, Token};
 use rowdy_compiler::Compiler;
 use rowdy_lexer::{
-    token::{QualifiedToken as Token, QualifiedTokenType as TokenType},
+    token::{
+        Keyword, Operator, QualifiedToken as Token, QualifiedTokenType as TokenType, SpecialChar,
+    },
     TokenIter,
 };
+use rowdy_location::Span;
+
+/// Parses the whole program, recovering from syntax errors so that all of them can be reported.
+pub fn parse_tokens(
+    tokens: TokenIter,
+    _compiler: &Compiler,
+) -> std::result::Result<Ast, Vec<ParseError>> {
+    let mut tokens = Tokens::new(tokens);
+    let ast = parse(&mut tokens).expect("Parsing a program recovers from every error");
+    if tokens.errors.is_empty() {
+        Ok(ast)
+    } else {
+        Err(tokens.errors)
+    }
+}
+
+/// The tokens being parsed, along with the errors which have been recovered from so far.
+///
+/// Errors recorded while in a `try_parse` that fails are discarded along with the rest of that attempt.
+#[derive(Debug, Clone)]
+pub struct Tokens<'a> {
+    iter: TokenIter<'a>,
+    errors: Vec<ParseError>,
+    /// Whether the lexer rejected the input just before the most recently returned token.
+    after_lex_error: bool,
+    /// The span of the token before the most recently returned one.
+    previous_span: Option<Span>,
+}
+
+impl<'a> Tokens<'a> {
+    pub fn new(iter: TokenIter<'a>) -> Self {
+        Self {
+            iter,
+            errors: Vec::new(),
+            after_lex_error: false,
+            previous_span: None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clone().next().is_none()
+    }
+
+    pub fn last_span(&self) -> Option<Span> {
+        self.iter.last_span()
+    }
+
+    pub fn errors(&self) -> &[ParseError] {
+        &self.errors
+    }
 
-pub fn parse_tokens(mut tokens: TokenIter, _compiler: &Compiler) -> Ast {
-    parse(&mut tokens).unwrap()
+    /// Records `error`, then goes back to `start` and skips ahead to the next likely
+    /// statement boundary.
+    /// That is just after a `;` or a `{ ... }` block, or just before the `}` closing the enclosing block.
+    /// A `;` inside parentheses or brackets, like those in a `for` header, isn't a boundary.
+    /// At the top level there is no enclosing block, so a stray `}` is skipped as well.
+    /// Returns the span of the skipped tokens.
+    fn recover(&mut self, error: ParseError, start: TokenIter<'a>, top_level: bool) -> Span {
+        // The lexer has already reported whatever was wrong with the input before this token.
+        // The syntax error is most likely caused by that, so it isn't reported a second time.
+        if !self.after_lex_error {
+            self.errors.push(error);
+        }
+        self.iter = start;
+
+        let mut depth = 0usize;
+        let mut paren_depth = 0usize;
+        let mut skipped: Option<Span> = None;
+        loop {
+            match peek(self) {
+                None => break,
+                Some(TokenType::SpecialChar(SpecialChar::RBrace)) if depth == 0 && !top_level => {
+                    break
+                }
+                _ => {}
+            }
+            let token = self.next().expect("peeked a token");
+            skipped = Some(match skipped {
+                Some(span) => span.combine(token.span),
+                None => token.span,
+            });
+            match token.typ {
+                TokenType::End if depth == 0 && paren_depth == 0 => break,
+                TokenType::SpecialChar(SpecialChar::LParen | SpecialChar::LBracket) => {
+                    paren_depth += 1
+                }
+                TokenType::SpecialChar(SpecialChar::RParen | SpecialChar::RBracket) => {
+                    paren_depth = paren_depth.saturating_sub(1)
+                }
+                TokenType::SpecialChar(SpecialChar::LBrace) => depth += 1,
+                TokenType::SpecialChar(SpecialChar::RBrace) => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        skipped
+            .or_else(|| self.last_span())
+            .expect("Recovered from an error before reading any tokens")
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token;
+
+    /// Lexer errors are reported separately, so the parser never sees them.
+    fn next(&mut self) -> Option<Self::Item> {
+        self.after_lex_error = false;
+        let previous_span = self.last_span();
+        for token in self.iter.by_ref() {
+            if let TokenType::Error(_) = token.typ {
+                self.after_lex_error = true;
+            } else {
+                self.previous_span = previous_span;
+                return Some(token);
+            }
+        }
+        None
+    }
 }
 
-type Result<T> = std::result::Result<T, ParseError>;
+/// Parse errors are boxed since they're much bigger than most of the nodes being parsed.
+type Result<T> = std::result::Result<T, Box<ParseError>>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum ParseError {
     UnexpectedToken {
-        expected: &'static str,
+        expected: &'static [&'static str],
         got: TokenType,
+        span: Span,
+        /// The span of the token before the unexpected one, if there was one.
+        previous: Option<Span>,
     },
-    OutOfTokens,
+    OutOfTokens {
+        expected: &'static [&'static str],
+        /// The span of the last token in the input.
+        span: Span,
+    },
+}
+
+impl ParseError {
+    /// Where the error occurred.
+    /// For unexpected tokens, this is the offending token itself.
+    pub fn span(&self) -> Span {
+        match self {
+            ParseError::UnexpectedToken { span, .. } | ParseError::OutOfTokens { span, .. } => {
+                *span
+            }
+        }
+    }
+
+    /// The span of the last token before the error, which anything missing would go after.
+    pub fn previous_span(&self) -> Option<Span> {
+        match self {
+            ParseError::UnexpectedToken { previous, .. } => *previous,
+            ParseError::OutOfTokens { span, .. } => Some(*span),
+        }
+    }
+
+    /// Descriptions of the things which would have been accepted instead.
+    pub fn expected(&self) -> &'static [&'static str] {
+        match self {
+            ParseError::UnexpectedToken { expected, .. }
+            | ParseError::OutOfTokens { expected, .. } => expected,
+        }
+    }
+
+    /// A description of what was found instead.
+    pub fn found(&self) -> String {
+        match self {
+            ParseError::UnexpectedToken { got, .. } => got.to_string(),
+            ParseError::OutOfTokens { .. } => "end of file".to_owned(),
+        }
+    }
+}
+
+/// Takes the next token, or fails with an error expecting `expected` if there are none left.
+fn next_token(tokens: &mut Tokens, expected: &'static [&'static str]) -> Result<Token> {
+    match tokens.next() {
+        Some(token) => Ok(token),
+        None => Err(Box::new(ParseError::OutOfTokens {
+            expected,
+            span: tokens
+                .last_span()
+                .expect("Ran out of tokens before reading any"),
+        })),
+    }
+}
+
+/// The error for `token` not being one of `expected`, where `token` was just taken from `tokens`.
+fn unexpected(tokens: &Tokens, token: Token, expected: &'static [&'static str]) -> Box<ParseError> {
+    Box::new(ParseError::UnexpectedToken {
+        expected,
+        got: token.typ,
+        span: token.span,
+        previous: tokens.previous_span,
+    })
 }
 
 pub trait Parse: Sized {
-    fn parse(tokens: &mut TokenIter) -> Result<Self>;
+    fn parse(tokens: &mut Tokens) -> Result<Self>;
 
-    fn try_parse(tokens: &mut TokenIter) -> Result<Self> {
+    fn try_parse(tokens: &mut Tokens) -> Result<Self> {
         let mut tokens_clone = tokens.clone();
         let result = Self::parse(&mut tokens_clone)?;
         *tokens = tokens_clone;
@@ -32,40 +220,46 @@ pub trait Parse: Sized {
 }
 
 #[inline]
-pub fn parse<T: Parse>(tokens: &mut TokenIter) -> Result<T> {
+pub fn parse<T: Parse>(tokens: &mut Tokens) -> Result<T> {
     T::parse(tokens)
 }
 
 #[inline]
-pub fn try_parse<T: Parse>(tokens: &mut TokenIter) -> Result<T> {
+pub fn try_parse<T: Parse>(tokens: &mut Tokens) -> Result<T> {
     T::try_parse(tokens)
 }
 
 impl Parse for Ast {
-    fn parse(tokens: &mut TokenIter) -> Result<Self> {
+    fn parse(tokens: &mut Tokens) -> Result<Self> {
         let mut functions = Vec::new();
         while !tokens.is_empty() {
-            functions.push(parse(tokens)?);
+            let start = tokens.iter.clone();
+            match parse(tokens) {
+                Ok(function) => functions.push(function),
+                Err(error) => {
+                    tokens.recover(*error, start, true);
+                }
+            }
         }
         Ok(Self { functions })
     }
 }
 
 impl Parse for Function {
-    fn parse(tokens: &mut TokenIter) -> Result<Self> {
+    fn parse(tokens: &mut Tokens) -> Result<Self> {
+        let mut attributes = Vec::new();
+        while let Some(TokenType::SpecialChar(SpecialChar::Hash)) = peek(tokens) {
+            attributes.push(parse(tokens)?);
+        }
         let return_type: Type = parse(tokens)?;
         let name = parse(tokens)?;
 
-        parse::<LParen>(tokens)?;
-        let mut parameters = Vec::new();
-        while let Ok(dec) = try_parse(tokens) {
-            parameters.push(dec);
-        }
-        parse::<RParen>(tokens)?;
+        let parameters = parse_parameters(tokens)?;
         let expr: BracedExpression = parse(tokens)?;
 
         Ok(Function {
             span: return_type.span().combine(expr.span()),
+            attributes,
             return_type,
             name,
             parameters,
@@ -74,8 +268,67 @@ impl Parse for Function {
     }
 }
 
+impl Parse for Attribute {
+    fn parse(tokens: &mut Tokens) -> Result<Self> {
+        let hash: Hash = parse(tokens)?;
+        parse::<LBracket>(tokens)?;
+        let name = parse(tokens)?;
+
+        let mut arguments = Vec::new();
+        if try_parse::<LParen>(tokens).is_ok() {
+            loop {
+                if try_parse::<RParen>(tokens).is_ok() {
+                    break;
+                }
+                arguments.push(parse(tokens)?);
+                if try_parse::<Token![,]>(tokens).is_err() {
+                    parse::<RParen>(tokens)?;
+                    break;
+                }
+            }
+        }
+        let end: RBracket = parse(tokens)?;
+
+        Ok(Attribute {
+            span: hash.span.combine(end.span),
+            name,
+            arguments,
+        })
+    }
+}
+
+/// Parses a parenthesized, comma separated parameter list.
+/// A trailing comma is allowed, and `(void)` is the same as `()`.
+fn parse_parameters(tokens: &mut Tokens) -> Result<Vec<Declaration>> {
+    parse::<LParen>(tokens)?;
+
+    let mut lookahead = tokens.clone();
+    if let (Ok(Symbol { text, .. }), Ok(_)) = (
+        parse::<Symbol>(&mut lookahead),
+        parse::<RParen>(&mut lookahead),
+    ) {
+        if text == "void" {
+            *tokens = lookahead;
+            return Ok(Vec::new());
+        }
+    }
+
+    let mut parameters = Vec::new();
+    loop {
+        if try_parse::<RParen>(tokens).is_ok() {
+            break;
+        }
+        parameters.push(parse(tokens)?);
+        if try_parse::<Token![,]>(tokens).is_err() {
+            parse::<RParen>(tokens)?;
+            break;
+        }
+    }
+    Ok(parameters)
+}
+
 impl Parse for Declaration {
-    fn parse(tokens: &mut TokenIter) -> Result<Self> {
+    fn parse(tokens: &mut Tokens) -> Result<Self> {
         let typ: Type = parse(tokens)?;
         let name: Symbol = parse(tokens)?;
         Ok(Declaration {
@@ -87,11 +340,21 @@ impl Parse for Declaration {
 }
 
 impl Parse for BracedExpression {
-    fn parse(tokens: &mut TokenIter) -> Result<Self> {
+    fn parse(tokens: &mut Tokens) -> Result<Self> {
         let start_brace: LBrace = parse(tokens)?;
         let mut statements = Vec::new();
-        while let Ok(statement) = try_parse(tokens) {
-            statements.push(statement);
+        while !matches!(
+            peek(tokens),
+            Some(TokenType::SpecialChar(SpecialChar::RBrace)) | None
+        ) {
+            let start = tokens.iter.clone();
+            match parse(tokens) {
+                Ok(statement) => statements.push(statement),
+                Err(error) => {
+                    let span = tokens.recover(*error, start, false);
+                    statements.push(Statement::Error(span));
+                }
+            }
         }
         let end_brace: RBrace = parse(tokens)?;
         Ok(BracedExpression {
@@ -102,113 +365,393 @@ impl Parse for BracedExpression {
 }
 
 impl Parse for Statement {
-    fn parse(tokens: &mut TokenIter) -> Result<Self> {
-        let first_symbol: Symbol = parse(tokens)?;
-        if let Ok(second_symbol) = try_parse::<Symbol>(tokens) {
-            // Declaration
-            let dec = Declaration {
-                span: first_symbol.span.combine(second_symbol.span),
-                typ: first_symbol.into(),
-                name: second_symbol,
-            };
-            if try_parse::<Token![=]>(tokens).is_ok() {
-                // Declaration w/ assignment
-                let expr = parse(tokens)?;
-                parse::<Token![;]>(tokens)?;
-                Ok(Statement::Declaration(dec, Some(expr)))
-            } else {
+    fn parse(tokens: &mut Tokens) -> Result<Self> {
+        match peek(tokens) {
+            Some(TokenType::Keyword(Keyword::If)) => Ok(Statement::If(parse(tokens)?)),
+            Some(TokenType::Keyword(Keyword::While)) => Ok(Statement::While(parse(tokens)?)),
+            Some(TokenType::Keyword(Keyword::For)) => Ok(Statement::For(parse(tokens)?)),
+            Some(TokenType::Keyword(Keyword::Return)) => Ok(Statement::Return(parse(tokens)?)),
+            Some(TokenType::SpecialChar(SpecialChar::LBrace)) => {
+                Ok(Statement::Block(parse(tokens)?))
+            }
+            _ => {
+                let statement = parse_simple_statement(tokens)?;
                 parse::<Token![;]>(tokens)?;
-                Ok(Statement::Declaration(dec, None))
+                Ok(statement)
             }
-        } else if try_parse::<Token![=]>(tokens).is_ok() {
-            // Assignment
-            let expr = parse(tokens)?;
-            parse::<Token![;]>(tokens)?;
+        }
+    }
+}
 
-            Ok(Statement::Assignment(first_symbol, expr))
+/// What can follow the first identifier of a statement.
+const SIMPLE_STATEMENT_CONTINUATION: &[&str] = &["identifier", "`=`", "`(`"];
+
+/// Parses a declaration, assignment, compound assignment or function call without its trailing `;`.
+/// These are the statements which are allowed in the header of a `for` loop.
+fn parse_simple_statement(tokens: &mut Tokens) -> Result<Statement> {
+    let first_symbol: Symbol = parse(tokens)?;
+    if let Ok(second_symbol) = try_parse::<Symbol>(tokens) {
+        // Declaration
+        let dec = Declaration {
+            span: first_symbol.span.combine(second_symbol.span),
+            typ: first_symbol.into(),
+            name: second_symbol,
+        };
+        if try_parse::<Token![=]>(tokens).is_ok() {
+            // Declaration w/ assignment
+            let expr = parse(tokens)?;
+            Ok(Statement::Declaration(dec, Some(expr)))
         } else {
-            // Function call
-            parse::<LParen>(tokens)?;
-            let mut exprs = Vec::new();
-            loop {
-                if try_parse::<RParen>(tokens).is_ok() {
-                    break;
-                }
-                exprs.push(parse(tokens)?);
-                if try_parse::<Token![,]>(tokens).is_err() {
-                    parse::<RParen>(tokens)?;
-                    break;
-                }
-            }
-            parse::<Token![;]>(tokens)?;
-            Ok(Statement::FunctionCall(first_symbol, exprs))
+            Ok(Statement::Declaration(dec, None))
         }
+    } else if try_parse::<Token![=]>(tokens).is_ok() {
+        // Assignment
+        let expr = parse(tokens)?;
+        Ok(Statement::Assignment(first_symbol, expr))
+    } else if let Some(op) = match peek(tokens) {
+        Some(TokenType::Operator(op)) => compound_assignment_op(&op),
+        _ => None,
+    } {
+        // Compound assignment, which is short for assigning `x op expr` to `x`
+        tokens.next();
+        let rhs: Expression = parse(tokens)?;
+        let expr = Expression::Binary(BinaryExpression {
+            span: first_symbol.span.combine(rhs.span()),
+            op,
+            lhs: Box::new(Expression::Symbol(Symbol {
+                text: first_symbol.text.clone(),
+                span: first_symbol.span,
+            })),
+            rhs: Box::new(rhs),
+        });
+        Ok(Statement::CompoundAssignment(first_symbol, expr))
+    } else if let Some(TokenType::SpecialChar(SpecialChar::LParen)) = peek(tokens) {
+        // Function call
+        Ok(Statement::FunctionCall(parse_call(first_symbol, tokens)?))
+    } else {
+        let token = next_token(tokens, SIMPLE_STATEMENT_CONTINUATION)?;
+        Err(unexpected(tokens, token, SIMPLE_STATEMENT_CONTINUATION))
+    }
+}
+
+/// Parses the argument list of a call to `name`, which has already been consumed.
+fn parse_call(name: Symbol, tokens: &mut Tokens) -> Result<FunctionCall> {
+    parse::<LParen>(tokens)?;
+    let mut arguments = Vec::new();
+    let end = loop {
+        if let Ok(end) = try_parse::<RParen>(tokens) {
+            break end;
+        }
+        arguments.push(parse(tokens)?);
+        if try_parse::<Token![,]>(tokens).is_err() {
+            break parse::<RParen>(tokens)?;
+        }
+    };
+    Ok(FunctionCall {
+        span: name.span.combine(end.span()),
+        name,
+        arguments,
+    })
+}
+
+impl Parse for IfStatement {
+    fn parse(tokens: &mut Tokens) -> Result<Self> {
+        let if_token: If = parse(tokens)?;
+        let condition = parse_condition(tokens)?;
+        let body: BracedExpression = parse(tokens)?;
+
+        let else_branch = if try_parse::<Else>(tokens).is_ok() {
+            if let Some(TokenType::Keyword(Keyword::If)) = peek(tokens) {
+                Some(ElseBranch::ElseIf(Box::new(parse(tokens)?)))
+            } else {
+                Some(ElseBranch::Else(parse(tokens)?))
+            }
+        } else {
+            None
+        };
+
+        let end_span = match &else_branch {
+            Some(else_branch) => else_branch.span(),
+            None => body.span(),
+        };
+        Ok(IfStatement {
+            span: if_token.span().combine(end_span),
+            condition,
+            body,
+            else_branch,
+        })
     }
 }
 
+impl Parse for WhileLoop {
+    fn parse(tokens: &mut Tokens) -> Result<Self> {
+        let while_token: While = parse(tokens)?;
+        let condition = parse_condition(tokens)?;
+        let body: BracedExpression = parse(tokens)?;
+        Ok(WhileLoop {
+            span: while_token.span().combine(body.span()),
+            condition,
+            body,
+        })
+    }
+}
+
+impl Parse for ForLoop {
+    fn parse(tokens: &mut Tokens) -> Result<Self> {
+        let for_token: For = parse(tokens)?;
+        parse::<LParen>(tokens)?;
+
+        let init = match peek(tokens) {
+            Some(TokenType::End) => None,
+            _ => Some(Box::new(parse_simple_statement(tokens)?)),
+        };
+        parse::<Token![;]>(tokens)?;
+
+        let condition = match peek(tokens) {
+            Some(TokenType::End) => None,
+            _ => Some(parse(tokens)?),
+        };
+        parse::<Token![;]>(tokens)?;
+
+        let step = match peek(tokens) {
+            Some(TokenType::SpecialChar(SpecialChar::RParen)) => None,
+            _ => Some(Box::new(parse_simple_statement(tokens)?)),
+        };
+        parse::<RParen>(tokens)?;
+
+        let body: BracedExpression = parse(tokens)?;
+        Ok(ForLoop {
+            span: for_token.span().combine(body.span()),
+            init,
+            condition,
+            step,
+            body,
+        })
+    }
+}
+
+impl Parse for ReturnStatement {
+    fn parse(tokens: &mut Tokens) -> Result<Self> {
+        let return_token: Return = parse(tokens)?;
+        let value: Option<Expression> = match peek(tokens) {
+            Some(TokenType::End) => None,
+            _ => Some(parse(tokens)?),
+        };
+        let end: End = parse(tokens)?;
+        Ok(ReturnStatement {
+            span: return_token.span().combine(end.span()),
+            value,
+        })
+    }
+}
+
+/// Parses the parenthesized condition of an `if` or `while`.
+fn parse_condition(tokens: &mut Tokens) -> Result<Expression> {
+    parse::<LParen>(tokens)?;
+    let condition = parse(tokens)?;
+    parse::<RParen>(tokens)?;
+    Ok(condition)
+}
+
+#[inline]
+fn peek(tokens: &Tokens) -> Option<TokenType> {
+    tokens.clone().next().map(|token| token.typ)
+}
+
 impl Parse for Expression {
-    fn parse(tokens: &mut TokenIter) -> Result<Self> {
-        match tokens.next().ok_or(ParseError::OutOfTokens)? {
-            Token {
-                typ: TokenType::SpecialChar(rowdy_lexer::token::SpecialChar::LBrace),
-                ..
-            } => Ok(Expression::Braced(parse(tokens)?)),
-            Token {
-                typ: TokenType::IntLit(x),
-                span,
-            } => Ok(Expression::IntLit(IntLit { span, value: x })),
-            Token {
-                typ: TokenType::FloatLit(x),
-                span,
-            } => Ok(Expression::FloatLit(FloatLit { span, value: x })),
-            Token {
-                typ: TokenType::Symbol(s),
-                span,
-            } => Ok(Expression::Symbol(Symbol { text: s, span })),
-            other => Err(ParseError::UnexpectedToken {
-                expected: "LBrace, IntLit, FloatLit, Symbol",
-                got: other.typ,
-            }),
+    fn parse(tokens: &mut Tokens) -> Result<Self> {
+        parse_expression(tokens, 0)
+    }
+}
+
+/// Precedence climbing over binary operators.
+/// Only operators whose left binding power is at least `min_bp` are consumed,
+/// everything else is left for the caller.
+fn parse_expression(tokens: &mut Tokens, min_bp: u8) -> Result<Expression> {
+    let mut lhs = parse_prefix(tokens)?;
+    while let Some(op) = match peek(tokens) {
+        Some(TokenType::Operator(op)) => binary_op(&op),
+        _ => None,
+    } {
+        let (left_bp, right_bp) = infix_binding_power(op);
+        if left_bp < min_bp {
+            break;
+        }
+        tokens.next();
+
+        let rhs = parse_expression(tokens, right_bp)?;
+        lhs = Expression::Binary(BinaryExpression {
+            span: lhs.span().combine(rhs.span()),
+            op,
+            lhs: Box::new(lhs),
+            rhs: Box::new(rhs),
+        });
+    }
+    Ok(lhs)
+}
+
+fn parse_prefix(tokens: &mut Tokens) -> Result<Expression> {
+    if let Some(TokenType::SpecialChar(SpecialChar::LBrace)) = peek(tokens) {
+        return Ok(Expression::Braced(parse(tokens)?));
+    }
+
+    let token = next_token(tokens, &["expression"])?;
+    if let TokenType::Operator(op) = &token.typ {
+        if let Some(op) = unary_op(op) {
+            let operand = parse_expression(tokens, PREFIX_BINDING_POWER)?;
+            return Ok(Expression::Unary(UnaryExpression {
+                span: token.span.combine(operand.span()),
+                op,
+                operand: Box::new(operand),
+            }));
+        }
+    }
+
+    match token {
+        Token {
+            typ: TokenType::SpecialChar(SpecialChar::LParen),
+            ..
+        } => {
+            let inner = parse_expression(tokens, 0)?;
+            parse::<RParen>(tokens)?;
+            Ok(inner)
+        }
+        Token {
+            typ: TokenType::IntLit(x),
+            span,
+        } => Ok(Expression::IntLit(IntLit { span, value: x })),
+        Token {
+            typ: TokenType::FloatLit(x),
+            span,
+        } => Ok(Expression::FloatLit(FloatLit { span, value: x })),
+        Token {
+            typ: TokenType::Keyword(keyword @ (Keyword::True | Keyword::False)),
+            span,
+        } => Ok(Expression::BoolLit(BoolLit {
+            span,
+            value: keyword == Keyword::True,
+        })),
+        Token {
+            typ: TokenType::StringLit(x),
+            span,
+        } => Ok(Expression::StringLit(StringLit { span, value: x })),
+        Token {
+            typ: TokenType::CharLit(x),
+            span,
+        } => Ok(Expression::CharLit(CharLit { span, value: x })),
+        Token {
+            typ: TokenType::Symbol(s),
+            span,
+        } => {
+            let symbol = Symbol { text: s, span };
+            if let Some(TokenType::SpecialChar(SpecialChar::LParen)) = peek(tokens) {
+                Ok(Expression::FunctionCall(parse_call(symbol, tokens)?))
+            } else {
+                Ok(Expression::Symbol(symbol))
+            }
         }
+        other => Err(unexpected(tokens, other, &["expression"])),
     }
 }
 
+const PREFIX_BINDING_POWER: u8 = 23;
+
+/// Returns the (left, right) binding power of a binary operator.
+/// All binary operators are left associative, so the right side binds slightly tighter.
+fn infix_binding_power(op: BinaryOp) -> (u8, u8) {
+    let precedence = match op {
+        BinaryOp::Or => 1,
+        BinaryOp::And => 2,
+        BinaryOp::BitOr => 3,
+        BinaryOp::BitXor => 4,
+        BinaryOp::BitAnd => 5,
+        BinaryOp::Equals | BinaryOp::NotEquals => 6,
+        BinaryOp::Less | BinaryOp::LessEquals | BinaryOp::Greater | BinaryOp::GreaterEquals => 7,
+        BinaryOp::ShiftLeft | BinaryOp::ShiftRight => 8,
+        BinaryOp::Add | BinaryOp::Sub => 9,
+        BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => 10,
+    };
+    (precedence * 2, precedence * 2 + 1)
+}
+
+fn binary_op(op: &Operator) -> Option<BinaryOp> {
+    Some(match op {
+        Operator::Plus => BinaryOp::Add,
+        Operator::Sub => BinaryOp::Sub,
+        Operator::Mul => BinaryOp::Mul,
+        Operator::Div => BinaryOp::Div,
+        Operator::Rem => BinaryOp::Rem,
+        Operator::Equals => BinaryOp::Equals,
+        Operator::NotEquals => BinaryOp::NotEquals,
+        Operator::Less => BinaryOp::Less,
+        Operator::LessEquals => BinaryOp::LessEquals,
+        Operator::Greater => BinaryOp::Greater,
+        Operator::GreaterEquals => BinaryOp::GreaterEquals,
+        Operator::And => BinaryOp::And,
+        Operator::Or => BinaryOp::Or,
+        Operator::BitAnd => BinaryOp::BitAnd,
+        Operator::BitOr => BinaryOp::BitOr,
+        Operator::BitXor => BinaryOp::BitXor,
+        Operator::ShiftLeft => BinaryOp::ShiftLeft,
+        Operator::ShiftRight => BinaryOp::ShiftRight,
+        _ => return None,
+    })
+}
+
+/// The operator applied by a compound assignment like `+=`.
+fn compound_assignment_op(op: &Operator) -> Option<BinaryOp> {
+    Some(match op {
+        Operator::PlusAssign => BinaryOp::Add,
+        Operator::SubAssign => BinaryOp::Sub,
+        Operator::MulAssign => BinaryOp::Mul,
+        Operator::DivAssign => BinaryOp::Div,
+        Operator::RemAssign => BinaryOp::Rem,
+        Operator::BitAndAssign => BinaryOp::BitAnd,
+        Operator::BitOrAssign => BinaryOp::BitOr,
+        Operator::BitXorAssign => BinaryOp::BitXor,
+        Operator::ShiftLeftAssign => BinaryOp::ShiftLeft,
+        Operator::ShiftRightAssign => BinaryOp::ShiftRight,
+        _ => return None,
+    })
+}
+
+fn unary_op(op: &Operator) -> Option<UnaryOp> {
+    Some(match op {
+        Operator::Sub => UnaryOp::Neg,
+        Operator::Not => UnaryOp::Not,
+        Operator::BitNot => UnaryOp::BitNot,
+        _ => return None,
+    })
+}
+
 impl Parse for Type {
-    fn parse(tokens: &mut TokenIter) -> Result<Self> {
+    fn parse(tokens: &mut Tokens) -> Result<Self> {
         Ok(parse::<Symbol>(tokens)?.into())
     }
 }
 
 impl Parse for Symbol {
-    fn parse(tokens: &mut TokenIter) -> Result<Self> {
-        match tokens.next() {
-            Some(Token {
+    fn parse(tokens: &mut Tokens) -> Result<Self> {
+        match next_token(tokens, &["identifier"])? {
+            Token {
                 typ: TokenType::Symbol(text),
                 span,
-            }) => Ok(Self { text, span }),
-            Some(other) => Err(ParseError::UnexpectedToken {
-                expected: "Symbol",
-                got: other.typ,
-            }),
-            None => Err(ParseError::OutOfTokens),
+            } => Ok(Self { text, span }),
+            other => Err(unexpected(tokens, other, &["identifier"])),
         }
     }
 }
 
 macro_rules! parse_node {
-    ($kind:ident :: $name:ident) => {
+    ($kind:ident :: $name:ident, $display:literal) => {
         impl Parse for $name {
-            fn parse(tokens: &mut TokenIter) -> Result<Self> {
-                match tokens.next() {
-                    Some(Token {
+            fn parse(tokens: &mut Tokens) -> Result<Self> {
+                match next_token(tokens, &[$display])? {
+                    Token {
                         typ: TokenType::$kind(rowdy_lexer::token::$kind::$name),
                         span,
-                    }) => Ok(Self { span }),
-                    Some(other) => Err(ParseError::UnexpectedToken {
-                        expected: stringify!($name),
-                        got: other.typ,
-                    }),
-                    None => Err(ParseError::OutOfTokens),
+                    } => Ok(Self { span }),
+                    other => Err(unexpected(tokens, other, &[$display])),
                 }
             }
         }
@@ -216,90 +759,326 @@ macro_rules! parse_node {
 }
 
 macro_rules! special_char_parse {
-    ($name:ident) => {
-        parse_node! {SpecialChar::$name}
+    ($name:ident, $display:literal) => {
+        parse_node! {SpecialChar::$name, $display}
     };
 }
 
-special_char_parse! {LParen}
-special_char_parse! {RParen}
-special_char_parse! {LBrace}
-special_char_parse! {RBrace}
-special_char_parse! {LBracket}
-special_char_parse! {RBracket}
-special_char_parse! {Comma}
+special_char_parse! {LParen, "`(`"}
+special_char_parse! {RParen, "`)`"}
+special_char_parse! {LBrace, "`{`"}
+special_char_parse! {RBrace, "`}`"}
+special_char_parse! {LBracket, "`[`"}
+special_char_parse! {RBracket, "`]`"}
+special_char_parse! {Comma, "`,`"}
+special_char_parse! {Hash, "`#`"}
 
 macro_rules! operator_parse {
-    ($name:ident) => {
-        parse_node! {Operator::$name}
+    ($name:ident, $display:literal) => {
+        parse_node! {Operator::$name, $display}
     };
 }
 
-operator_parse! {Plus}
-operator_parse! {PlusAssign}
-operator_parse! {Increment}
-operator_parse! {Sub}
-operator_parse! {SubAssign}
-operator_parse! {Decrement}
-operator_parse! {Assign}
-operator_parse! {Equals}
+operator_parse! {Plus, "`+`"}
+operator_parse! {PlusAssign, "`+=`"}
+operator_parse! {Increment, "`++`"}
+operator_parse! {Sub, "`-`"}
+operator_parse! {SubAssign, "`-=`"}
+operator_parse! {Decrement, "`--`"}
+operator_parse! {Assign, "`=`"}
+operator_parse! {Equals, "`==`"}
+operator_parse! {NotEquals, "`!=`"}
+operator_parse! {Mul, "`*`"}
+operator_parse! {MulAssign, "`*=`"}
+operator_parse! {Div, "`/`"}
+operator_parse! {DivAssign, "`/=`"}
+operator_parse! {Rem, "`%`"}
+operator_parse! {RemAssign, "`%=`"}
+operator_parse! {Less, "`<`"}
+operator_parse! {LessEquals, "`<=`"}
+operator_parse! {Greater, "`>`"}
+operator_parse! {GreaterEquals, "`>=`"}
+operator_parse! {Not, "`!`"}
+operator_parse! {And, "`&&`"}
+operator_parse! {Or, "`||`"}
+operator_parse! {BitAnd, "`&`"}
+operator_parse! {BitAndAssign, "`&=`"}
+operator_parse! {BitOr, "`|`"}
+operator_parse! {BitOrAssign, "`|=`"}
+operator_parse! {BitXor, "`^`"}
+operator_parse! {BitXorAssign, "`^=`"}
+operator_parse! {BitNot, "`~`"}
+operator_parse! {ShiftLeft, "`<<`"}
+operator_parse! {ShiftLeftAssign, "`<<=`"}
+operator_parse! {ShiftRight, "`>>`"}
+operator_parse! {ShiftRightAssign, "`>>=`"}
 
 macro_rules! keyword_parse {
-    ($name:ident) => {
-        parse_node! {Keyword::$name}
+    ($name:ident, $display:literal) => {
+        parse_node! {Keyword::$name, $display}
     };
 }
 
-keyword_parse! {If}
-keyword_parse! {Else}
-keyword_parse! {While}
-keyword_parse! {For}
-keyword_parse! {Return}
+keyword_parse! {If, "`if`"}
+keyword_parse! {Else, "`else`"}
+keyword_parse! {While, "`while`"}
+keyword_parse! {For, "`for`"}
+keyword_parse! {Return, "`return`"}
+keyword_parse! {True, "`true`"}
+keyword_parse! {False, "`false`"}
 
 impl Parse for End {
-    fn parse(tokens: &mut TokenIter) -> Result<Self> {
-        match tokens.next() {
-            Some(Token {
+    fn parse(tokens: &mut Tokens) -> Result<Self> {
+        match next_token(tokens, &["`;`"])? {
+            Token {
                 typ: TokenType::End,
                 span,
-            }) => Ok(Self { span }),
-            Some(other) => Err(ParseError::UnexpectedToken {
-                expected: "End",
-                got: other.typ,
-            }),
-            None => Err(ParseError::OutOfTokens),
+            } => Ok(Self { span }),
+            other => Err(unexpected(tokens, other, &["`;`"])),
         }
     }
 }
 
 impl Parse for IntLit {
-    fn parse(tokens: &mut TokenIter) -> Result<Self> {
-        match tokens.next() {
-            Some(Token {
+    fn parse(tokens: &mut Tokens) -> Result<Self> {
+        match next_token(tokens, &["integer literal"])? {
+            Token {
                 typ: TokenType::IntLit(value),
                 span,
-            }) => Ok(Self { span, value }),
-            Some(other) => Err(ParseError::UnexpectedToken {
-                expected: "IntLit",
-                got: other.typ,
-            }),
-            None => Err(ParseError::OutOfTokens),
+            } => Ok(Self { span, value }),
+            other => Err(unexpected(tokens, other, &["integer literal"])),
         }
     }
 }
 
 impl Parse for FloatLit {
-    fn parse(tokens: &mut TokenIter) -> Result<Self> {
-        match tokens.next() {
-            Some(Token {
+    fn parse(tokens: &mut Tokens) -> Result<Self> {
+        match next_token(tokens, &["float literal"])? {
+            Token {
                 typ: TokenType::FloatLit(value),
                 span,
-            }) => Ok(Self { span, value }),
-            Some(other) => Err(ParseError::UnexpectedToken {
-                expected: "IntLit",
-                got: other.typ,
+            } => Ok(Self { span, value }),
+            other => Err(unexpected(tokens, other, &["float literal"])),
+        }
+    }
+}
+
+impl Parse for BoolLit {
+    fn parse(tokens: &mut Tokens) -> Result<Self> {
+        match next_token(tokens, &["`true`", "`false`"])? {
+            Token {
+                typ: TokenType::Keyword(keyword @ (Keyword::True | Keyword::False)),
+                span,
+            } => Ok(Self {
+                span,
+                value: keyword == Keyword::True,
             }),
-            None => Err(ParseError::OutOfTokens),
+            other => Err(unexpected(tokens, other, &["`true`", "`false`"])),
+        }
+    }
+}
+
+impl Parse for StringLit {
+    fn parse(tokens: &mut Tokens) -> Result<Self> {
+        match next_token(tokens, &["string literal"])? {
+            Token {
+                typ: TokenType::StringLit(value),
+                span,
+            } => Ok(Self { span, value }),
+            other => Err(unexpected(tokens, other, &["string literal"])),
+        }
+    }
+}
+
+impl Parse for CharLit {
+    fn parse(tokens: &mut Tokens) -> Result<Self> {
+        match next_token(tokens, &["character literal"])? {
+            Token {
+                typ: TokenType::CharLit(value),
+                span,
+            } => Ok(Self { span, value }),
+            other => Err(unexpected(tokens, other, &["character literal"])),
         }
     }
 }
+
+#[test]
+fn operator_precedence() {
+    let mut tokens = Tokens::new(rowdy_lexer::tokenize_str("a + b * 2 == -(c - 1) || d"));
+    let expr: Expression = parse(&mut tokens).unwrap();
+    assert!(tokens.is_empty());
+
+    let Expression::Binary(or) = expr else {
+        panic!("expected a binary expression, got {expr:?}")
+    };
+    assert_eq!(or.op, BinaryOp::Or);
+    let Expression::Binary(equals) = *or.lhs else {
+        panic!("expected `==` on the left of `||`")
+    };
+    assert_eq!(equals.op, BinaryOp::Equals);
+    let Expression::Binary(add) = *equals.lhs else {
+        panic!("expected `+` on the left of `==`")
+    };
+    assert_eq!(add.op, BinaryOp::Add);
+    assert!(matches!(
+        *add.rhs,
+        Expression::Binary(BinaryExpression {
+            op: BinaryOp::Mul,
+            ..
+        })
+    ));
+    let Expression::Unary(neg) = *equals.rhs else {
+        panic!("expected `-` on the right of `==`")
+    };
+    assert_eq!(neg.op, UnaryOp::Neg);
+    assert!(matches!(
+        *neg.operand,
+        Expression::Binary(BinaryExpression {
+            op: BinaryOp::Sub,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn compound_assignments() {
+    let mut tokens = Tokens::new(rowdy_lexer::tokenize_str("{ x += 1; x <<= y * 2; }"));
+    let braced: BracedExpression = parse(&mut tokens).unwrap();
+    assert!(tokens.is_empty());
+
+    let ops: Vec<_> = braced
+        .statements
+        .iter()
+        .map(|statement| match statement {
+            Statement::CompoundAssignment(symbol, Expression::Binary(binary)) => {
+                assert_eq!(symbol.text, "x");
+                assert!(matches!(&*binary.lhs, Expression::Symbol(lhs) if lhs.text == "x"));
+                binary.op
+            }
+            _ => panic!("expected an assignment of a binary expression, got {statement:?}"),
+        })
+        .collect();
+    assert_eq!(ops, [BinaryOp::Add, BinaryOp::ShiftLeft]);
+}
+
+#[test]
+fn control_flow_statements() {
+    let mut tokens = Tokens::new(rowdy_lexer::tokenize_str(
+        "{ if (a) { } else if (b) { } else { } while (c) { } for (;;) { } for (int i = 0; i < 3; i = i + 1) { } { } }",
+    ));
+    let braced: BracedExpression = parse(&mut tokens).unwrap();
+    assert!(tokens.is_empty());
+
+    let [Statement::If(if_statement), Statement::While(_), Statement::For(empty_for), Statement::For(full_for), Statement::Block(_)] =
+        braced.statements.as_slice()
+    else {
+        panic!("unexpected statements: {:?}", braced.statements)
+    };
+    let Some(ElseBranch::ElseIf(else_if)) = &if_statement.else_branch else {
+        panic!("expected an else if branch")
+    };
+    assert!(matches!(else_if.else_branch, Some(ElseBranch::Else(_))));
+    assert!(empty_for.init.is_none() && empty_for.condition.is_none() && empty_for.step.is_none());
+    assert!(full_for.init.is_some() && full_for.condition.is_some() && full_for.step.is_some());
+}
+
+#[test]
+fn parameter_lists() {
+    for (code, count) in [
+        ("int f() {}", 0),
+        ("int f(void) {}", 0),
+        ("int f(int a) {}", 1),
+        ("int f(int a, float b) {}", 2),
+        ("int f(int a, float b,) {}", 2),
+    ] {
+        let function: Function = parse(&mut Tokens::new(rowdy_lexer::tokenize_str(code))).unwrap();
+        assert_eq!(function.parameters.len(), count, "{code}");
+    }
+
+    assert!(
+        parse::<Function>(&mut Tokens::new(rowdy_lexer::tokenize_str(
+            "int f(int a int b) {}"
+        )))
+        .is_err()
+    );
+    assert!(parse::<Function>(&mut Tokens::new(rowdy_lexer::tokenize_str("int f(,) {}"))).is_err());
+}
+
+#[test]
+fn attributes() {
+    let function: Function = parse(&mut Tokens::new(rowdy_lexer::tokenize_str(
+        "#[allow(unused_variable, shadowing)] #[inline] int f() {}",
+    )))
+    .unwrap();
+    let [allow, inline] = &function.attributes[..] else {
+        panic!("expected two attributes");
+    };
+    assert_eq!(allow.name.text, "allow");
+    let arguments: Vec<_> = allow.arguments.iter().map(|arg| &arg.text[..]).collect();
+    assert_eq!(arguments, ["unused_variable", "shadowing"]);
+    assert_eq!(inline.name.text, "inline");
+    assert!(inline.arguments.is_empty());
+    assert_eq!(function.name.text, "f");
+
+    assert!(
+        parse::<Function>(&mut Tokens::new(rowdy_lexer::tokenize_str(
+            "#[allow(unused_variable) int f() {}"
+        )))
+        .is_err()
+    );
+}
+
+#[test]
+fn recovers_from_syntax_errors() {
+    let mut tokens = Tokens::new(rowdy_lexer::tokenize_str(
+        "int main() { int x = ; x = 1; if (x) { y = = 2; } return } int f( { } int g() { }",
+    ));
+    let ast: Ast = parse(&mut tokens).unwrap();
+    assert_eq!(tokens.errors().len(), 4);
+
+    let names: Vec<_> = ast.functions.iter().map(|f| f.name.text.as_str()).collect();
+    assert_eq!(names, ["main", "g"]);
+    let main = &ast.functions[0].expr.statements;
+    assert!(matches!(
+        main.as_slice(),
+        [
+            Statement::Error(_),
+            Statement::Assignment(..),
+            Statement::If(_),
+            Statement::Error(_)
+        ]
+    ));
+}
+
+#[test]
+fn recovers_inside_for_headers() {
+    let mut tokens = Tokens::new(rowdy_lexer::tokenize_str(
+        "int main() { for (int i = 0 i < 3; i = i + 1) { } return 0; }",
+    ));
+    let ast: Ast = parse(&mut tokens).unwrap();
+    assert_eq!(tokens.errors().len(), 1);
+    assert!(matches!(
+        ast.functions[0].expr.statements.as_slice(),
+        [Statement::Error(_), Statement::Return(_)]
+    ));
+}
+
+#[test]
+fn operator_tokens() {
+    let mut tokens = Tokens::new(rowdy_lexer::tokenize_str("<<= && != %"));
+    parse::<Token![<<=]>(&mut tokens).unwrap();
+    parse::<Token![&&]>(&mut tokens).unwrap();
+    assert!(try_parse::<Token![==]>(&mut tokens).is_err());
+    parse::<Token![!=]>(&mut tokens).unwrap();
+    parse::<Token![%]>(&mut tokens).unwrap();
+    assert!(tokens.is_empty());
+}
+
+#[test]
+fn lexer_errors_are_not_reported_twice() {
+    let mut tokens = Tokens::new(rowdy_lexer::tokenize_str(
+        r#"int main() { string s = "\q"; char c = 'x'; int y = 1 2; }"#,
+    ));
+    parse::<Ast>(&mut tokens).unwrap();
+    assert_eq!(tokens.errors().len(), 1);
+}
@@ -1,12 +1,15 @@
 use rowdy_ast::{base, typed, Spanned};
 use rowdy_compiler::Compiler;
 use rowdy_diagnostics as diagnostic;
-use rowdy_types::{FnSignature, TypeID};
-use std::collections::BTreeMap;
+use rowdy_location::{Location, Span};
+use rowdy_types::{FnSignature, Primitive, TypeID};
+use std::collections::{BTreeMap, BTreeSet};
 
+/// Gives every node of the program a type, emitting any errors to the compiler's diagnostics.
+/// Nodes whose type couldn't be worked out are given an error type,
+/// so the result mustn't be used if there were errors.
 pub fn type_check(ast: &base::Ast, compiler: &Compiler) -> typed::Ast {
-    let mut checker = TypeChecker::new(compiler);
-    checker.visit(ast)
+    TypeChecker::new(compiler).visit(ast)
 }
 
 // 1. go through and find all type definitions
@@ -17,62 +20,562 @@ pub fn type_check(ast: &base::Ast, compiler: &Compiler) -> typed::Ast {
 #[derive(Debug)]
 struct TypeChecker<'a> {
     functions: BTreeMap<String, FnSignature>,
-    types: BTreeMap<String, TypeID>,
-    next_key: TypeID,
-    symbol_table: BTreeMap<String, TypeID>,
+    /// The functions which are called somewhere in the program.
+    called: BTreeSet<String>,
+    /// The variables in scope, innermost scope last.
+    scopes: Vec<BTreeMap<String, Variable>>,
+    /// Return type of the function currently being checked, along with where it was written.
+    return_type: Option<(TypeID, Span)>,
+    /// The lints turned off by `#[allow(...)]` on the function currently being checked.
+    allowed_lints: Vec<diagnostic::Lint>,
     compiler: &'a Compiler,
 }
 
+#[derive(Debug, Clone, Copy)]
+struct Variable {
+    typed: TypeID,
+    /// The name in the variable's declaration.
+    span: Span,
+    /// Whether the variable's value is read anywhere.
+    used: bool,
+}
+
 impl<'a> TypeChecker<'a> {
     fn new(compiler: &'a Compiler) -> Self {
         Self {
             functions: BTreeMap::new(),
-            types: BTreeMap::new(),
-            next_key: 0,
-            symbol_table: BTreeMap::new(),
+            called: BTreeSet::new(),
+            scopes: Vec::new(),
+            return_type: None,
+            allowed_lints: Vec::new(),
             compiler,
         }
     }
 
-    fn type_name_lookup(&mut self, name: &str) -> TypeID {
-        match self.types.get(name) {
-            Some(id) => *id,
-            None => {
-                let key = self.next_key;
-                self.types.insert(name.to_owned(), key);
-                self.next_key += 1;
-                key
+    /// Starts a diagnostic for `lint` at the level it's set to,
+    /// or returns `None` if it's allowed on the command line or for the current function.
+    fn lint(
+        &self,
+        lint: diagnostic::Lint,
+        span: Span,
+        kind: diagnostic::ErrorKind,
+    ) -> Option<diagnostic::Diagnostic> {
+        if self.allowed_lints.contains(&lint) {
+            return None;
+        }
+        let lints = &self.compiler.config.lints;
+        let diagnostic = match lints.level(lint) {
+            diagnostic::LintLevel::Allow => return None,
+            diagnostic::LintLevel::Warn => diagnostic::Diagnostic::warning(span, kind),
+            diagnostic::LintLevel::Deny => diagnostic::Diagnostic::error(span, kind),
+        };
+        Some(diagnostic.with_note(lints.reason(lint)))
+    }
+
+    /// Finds the type a type name refers to, reporting an error if there isn't one.
+    fn lookup_type(&mut self, name: &base::Symbol) -> TypeID {
+        if let Some(primitive) = Primitive::from_name(&name.text) {
+            return primitive.id();
+        }
+        let mut error = diagnostic::Diagnostic::error(
+            name.span,
+            diagnostic::ErrorKind::E0014 {
+                name: name.text.clone(),
+            },
+        )
+        .with_label("not a type");
+        let names = Primitive::ALL.into_iter().map(Primitive::name);
+        if let Some(suggestion) = diagnostic::did_you_mean(&name.text, names) {
+            error = error.with_suggestion(typo_suggestion("a type", name.span, suggestion));
+        }
+        self.compiler.diagnostics.emit(error);
+        ERROR_TYPE
+    }
+
+    /// Adds a variable to the innermost scope.
+    /// If that scope already has a variable with the same name, the new variable replaces it
+    /// and the old one is returned.
+    fn declare(&mut self, name: &typed::Symbol) -> Option<Variable> {
+        let (scope, outer_scopes) = self
+            .scopes
+            .split_last_mut()
+            .expect("Declared a variable outside of any scope");
+        let variable = Variable {
+            typed: name.typed,
+            span: name.span,
+            used: false,
+        };
+        if let Some(previous) = scope.insert(name.text.clone(), variable) {
+            return Some(previous);
+        }
+        let shadowed = outer_scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(&name.text))
+            .copied();
+        if let Some(shadowed) = shadowed {
+            let kind = diagnostic::ErrorKind::E0020 {
+                name: name.text.clone(),
+            };
+            if let Some(warning) = self.lint(diagnostic::Lint::Shadowing, name.span, kind) {
+                self.compiler
+                    .diagnostics
+                    .emit(warning.with_secondary(shadowed.span, "shadowed variable declared here"));
             }
         }
+        None
     }
-}
 
-impl Visit<base::Ast> for TypeChecker<'_> {
-    type Output = typed::Ast;
+    /// Leaves the innermost scope, reporting the variables in it which were never read.
+    fn pop_scope(&mut self) {
+        let scope = self
+            .scopes
+            .pop()
+            .expect("Popped a scope without pushing one");
+        let mut unused: Vec<_> = scope
+            .into_iter()
+            .filter(|(name, variable)| !variable.used && !name.starts_with('_'))
+            .collect();
+        unused.sort_by_key(|(_, variable)| variable.span.start.char_num);
+        for (name, variable) in unused {
+            let kind = diagnostic::ErrorKind::E0026 { name: name.clone() };
+            if let Some(warning) = self.lint(diagnostic::Lint::UnusedVariable, variable.span, kind)
+            {
+                self.compiler.diagnostics.emit(
+                    warning.with_suggestion(
+                        diagnostic::Suggestion::new(
+                            format!(
+                                "if this is intentional, prefix it with an underscore: `_{name}`"
+                            ),
+                            diagnostic::Applicability::MaybeIncorrect,
+                        )
+                        .replace(variable.span, format!("_{name}")),
+                    ),
+                );
+            }
+        }
+    }
 
-    fn visit(&mut self, node: &base::Ast) -> Self::Output {
-        self.functions = node
-            .functions
+    fn declare_local(&mut self, declaration: &typed::Declaration) {
+        if let Some(previous) = self.declare(&declaration.name) {
+            let name = &declaration.name.text;
+            self.compiler.diagnostics.emit(
+                diagnostic::Diagnostic::error(
+                    declaration.name.span,
+                    diagnostic::ErrorKind::E0019 { name: name.clone() },
+                )
+                .with_label(format!("`{name}` redeclared here"))
+                .with_secondary(previous.span, format!("previous declaration of `{name}`"))
+                .with_suggestion(
+                    diagnostic::Suggestion::new(
+                        format!("to change the value of `{name}`, assign to it instead"),
+                        diagnostic::Applicability::MaybeIncorrect,
+                    )
+                    .replace(
+                        Span::from_start_end(declaration.span.start, declaration.name.span.end),
+                        name.clone(),
+                    ),
+                ),
+            );
+        }
+    }
+
+    /// Finds the variable `name` refers to in the current scope.
+    fn lookup_variable(&self, name: &str) -> Option<Variable> {
+        self.scopes
             .iter()
-            .map(|function| {
-                (
-                    function.name.text.clone(),
-                    FnSignature {
-                        return_type: self.type_name_lookup(&function.return_type.symbol.text),
-                        parameter_types: function
-                            .parameters
-                            .iter()
-                            .map(|dec| &dec.typ.symbol.text)
-                            .map(|name| self.type_name_lookup(name))
-                            .collect(),
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .copied()
+    }
+
+    /// Records that the value of the variable `name` refers to is read.
+    fn mark_used(&mut self, name: &str) {
+        self.set_used(name, true);
+    }
+
+    fn set_used(&mut self, name: &str, used: bool) {
+        let variable = self
+            .scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(name));
+        if let Some(variable) = variable {
+            variable.used = used;
+        }
+    }
+
+    /// Checks that `expression` can be assigned to the variable `target`.
+    fn check_assignment(
+        &mut self,
+        target: &base::Symbol,
+        expression: &base::Expression,
+    ) -> (typed::Symbol, typed::Expression) {
+        let target = self.visit(target);
+        let mut expression = self.visit(expression);
+        self.type_int_literal(&mut expression, target.typed);
+        let reason = self.lookup_variable(&target.text).map(|variable| {
+            (
+                variable.span,
+                format!("expected due to the type of `{}`", target.text),
+            )
+        });
+        self.expect_type(expression.span(), target.typed, expression.typed, reason);
+        (target, expression)
+    }
+
+    /// Checks a call of an integer type, like `i64(x)`, which converts an integer to that type.
+    /// Returns the type converted to.
+    fn check_conversion(
+        &mut self,
+        node: &base::FunctionCall,
+        arguments: &mut [typed::Expression],
+    ) -> TypeID {
+        let target = Primitive::from_name(&node.name.text)
+            .expect("Checked a conversion to a type which doesn't exist")
+            .id();
+        let note = format!(
+            "`{}(...)` converts a value of any integer type to `{}`",
+            node.name.text, node.name.text
+        );
+        let [argument] = arguments else {
+            self.compiler.diagnostics.emit(
+                diagnostic::Diagnostic::error(
+                    node.span,
+                    diagnostic::ErrorKind::E0017 {
+                        name: node.name.text.clone(),
+                        expected: 1,
+                        found: arguments.len(),
                     },
                 )
-            })
+                .with_label("expected 1 argument")
+                .with_note(note),
+            );
+            return target;
+        };
+        self.type_int_literal(argument, target);
+        // Only the error type isn't primitive, and its error has been reported already.
+        let is_integer = Primitive::from_id(argument.typed).is_none_or(Primitive::is_integer);
+        if !is_integer {
+            let (expected, found) = (type_name(target), type_name(argument.typed));
+            self.compiler.diagnostics.emit(
+                diagnostic::Diagnostic::error(
+                    argument.span(),
+                    diagnostic::ErrorKind::E0018 {
+                        expected: expected.clone(),
+                        found: found.clone(),
+                    },
+                )
+                .with_label(format!("expected an integer, found `{found}`"))
+                .with_note(note),
+            );
+        }
+        target
+    }
+
+    /// Gives an integer literal, or a negated one, the integer type `expected` instead of `int`,
+    /// so that literals can be used as any integer type without a conversion.
+    /// Reports the literal if its value doesn't fit in `expected`.
+    /// Leaves any other expression alone for `expect_type` to check.
+    fn type_int_literal(&mut self, expression: &mut typed::Expression, expected: TypeID) {
+        let Some((min, max)) = Primitive::from_id(expected).and_then(Primitive::integer_range)
+        else {
+            return;
+        };
+        let span = expression.span();
+        let value = match &mut expression.inner {
+            typed::ExpressionInner::IntLit(lit) => {
+                lit.typed = expected;
+                i128::from(lit.value)
+            }
+            typed::ExpressionInner::Unary(unary) if unary.op == base::UnaryOp::Neg => {
+                let typed::ExpressionInner::IntLit(lit) = &mut unary.operand.inner else {
+                    return;
+                };
+                lit.typed = expected;
+                unary.operand.typed = expected;
+                unary.typed = expected;
+                -i128::from(lit.value)
+            }
+            _ => return,
+        };
+        expression.typed = expected;
+        if !(min..=max).contains(&value) {
+            let typ = type_name(expected);
+            self.compiler.diagnostics.emit(
+                diagnostic::Diagnostic::error(
+                    span,
+                    diagnostic::ErrorKind::E0034 { typ: typ.clone() },
+                )
+                .with_label(format!("`{value}` doesn't fit in `{typ}`"))
+                .with_note(format!("`{typ}` holds values from `{min}` to `{max}`")),
+            );
+        }
+    }
+
+    /// Checks the statements of a block in the current scope, rather than giving it its own.
+    fn check_block(&mut self, node: &base::BracedExpression) -> typed::BracedExpression {
+        let statements = node
+            .statements
+            .iter()
+            .map(|statement| self.visit(statement))
             .collect();
 
+        let returning = node.statements.iter().position(never_finishes);
+        if let Some(returning) = returning {
+            if let Some(unreachable) = node.statements.get(returning + 1) {
+                let lint = diagnostic::Lint::UnreachableCode;
+                let kind = diagnostic::ErrorKind::E0028;
+                if let Some(warning) = self.lint(lint, unreachable.span(), kind) {
+                    self.compiler.diagnostics.emit(
+                        warning.with_label("unreachable statement").with_secondary(
+                            node.statements[returning].span(),
+                            "any code following this is unreachable",
+                        ),
+                    );
+                }
+            }
+        }
+
+        typed::BracedExpression {
+            span: node.span,
+            statements,
+            typed: Primitive::Void.id(),
+        }
+    }
+
+    /// Reports a program without a `main` function, or whose `main` isn't `int main()`.
+    /// `definition` is the name of `main` in its definition.
+    fn check_main(&mut self, definition: Option<Span>) {
+        let Some(definition) = definition else {
+            let end = Span::from_loc(end_location(&self.compiler.code));
+            self.compiler.diagnostics.emit(
+                diagnostic::Diagnostic::error(end, diagnostic::ErrorKind::E0030)
+                    .with_label("consider adding a `main` function")
+                    .with_note("the program starts by calling `int main()`"),
+            );
+            return;
+        };
+        let signature = &self.functions["main"];
+        if types_match(Primitive::Int.id(), signature.return_type)
+            && signature.parameter_types.is_empty()
+        {
+            return;
+        }
+        self.compiler.diagnostics.emit(
+            diagnostic::Diagnostic::error(
+                definition,
+                diagnostic::ErrorKind::E0031 {
+                    found: signature_text("main", signature),
+                },
+            )
+            .with_label("should be `int main()`")
+            .with_note("the program's exit code is the `int` returned by `main`"),
+        );
+    }
+
+    /// Reports a function which should return a value, but can reach the end of its body without returning.
+    /// Like in C, `main` is allowed to reach its end, which returns 0.
+    fn check_return(&mut self, node: &base::Function, return_type: TypeID) {
+        let statements = &node.expr.statements;
+        // The error type's error has been reported already.
+        if matches!(
+            Primitive::from_id(return_type),
+            None | Some(Primitive::Void)
+        ) || node.name.text == "main"
+            || statements.iter().any(never_finishes)
+        {
+            return;
+        }
+        let end = Span::from_loc(node.expr.span.end);
+        let mut error = diagnostic::Diagnostic::error(
+            end,
+            diagnostic::ErrorKind::E0033 {
+                name: node.name.text.clone(),
+            },
+        )
+        .with_label("can reach the end of the function without returning")
+        .with_secondary(
+            node.return_type.symbol.span,
+            format!(
+                "expected `{}` because of this return type",
+                type_name(return_type)
+            ),
+        );
+        if let Some(value) = placeholder_value(return_type) {
+            let code = &self.compiler.code;
+            // The `return` goes after whatever comes last before the closing `}`,
+            // which is the last statement's `;` or `}`, or the opening `{` of an empty body.
+            let last_char = code[..end.start.char_num]
+                .trim_end()
+                .char_indices()
+                .next_back()
+                .map_or(0, |(i, _)| i);
+            // Put the `return` on its own line, unless the last statement shares its line with something else.
+            let separator = match statements.last() {
+                Some(last) => {
+                    let start = last.span().start.char_num;
+                    let line_start = code[..start].rfind('\n').map_or(0, |i| i + 1);
+                    let indent = &code[line_start..start];
+                    if indent.trim().is_empty() {
+                        format!("\n{indent}")
+                    } else {
+                        " ".to_owned()
+                    }
+                }
+                None => " ".to_owned(),
+            };
+            error = error.with_suggestion(
+                diagnostic::Suggestion::new(
+                    format!("consider returning a value at the end: `return {value};`"),
+                    diagnostic::Applicability::MaybeIncorrect,
+                )
+                .insert_after(
+                    Span::from_loc(location_at(code, last_char)),
+                    format!("{separator}return {value};"),
+                ),
+            );
+        }
+        self.compiler.diagnostics.emit(error);
+    }
+
+    /// Reports attributes which don't exist and lints in `#[allow(...)]` which don't exist.
+    fn check_attributes(&mut self, attributes: &[base::Attribute]) {
+        for attribute in attributes {
+            let name = &attribute.name;
+            if name.text != "allow" {
+                let mut error = diagnostic::Diagnostic::error(
+                    name.span,
+                    diagnostic::ErrorKind::E0024 {
+                        name: name.text.clone(),
+                    },
+                );
+                if let Some(suggestion) = diagnostic::did_you_mean(&name.text, ["allow"]) {
+                    error = error.with_suggestion(typo_suggestion(
+                        "an attribute",
+                        name.span,
+                        suggestion,
+                    ));
+                }
+                self.compiler.diagnostics.emit(error);
+                continue;
+            }
+            for argument in &attribute.arguments {
+                if diagnostic::Lint::from_name(&argument.text).is_some() {
+                    continue;
+                }
+                let mut error = diagnostic::Diagnostic::error(
+                    argument.span,
+                    diagnostic::ErrorKind::E0025 {
+                        name: argument.text.clone(),
+                    },
+                );
+                let names = diagnostic::Lint::ALL.map(diagnostic::Lint::name);
+                if let Some(suggestion) = diagnostic::did_you_mean(&argument.text, names) {
+                    error =
+                        error.with_suggestion(typo_suggestion("a lint", argument.span, suggestion));
+                }
+                self.compiler.diagnostics.emit(error);
+            }
+        }
+    }
+
+    /// Conditions of control flow and operands of logical operators must be `bool`.
+    fn check_condition(&mut self, condition: &typed::Expression) {
+        self.expect_type(
+            condition.span(),
+            Primitive::Bool.id(),
+            condition.typed,
+            None,
+        );
+    }
+
+    /// Reports a type mismatch if `found` isn't `expected`.
+    /// `reason` points at whatever made `expected` the type to expect.
+    fn expect_type(
+        &mut self,
+        span: Span,
+        expected: TypeID,
+        found: TypeID,
+        reason: Option<(Span, String)>,
+    ) {
+        if types_match(expected, found) {
+            return;
+        }
+        let (expected, found) = (type_name(expected), type_name(found));
+        let mut error = diagnostic::Diagnostic::error(
+            span,
+            diagnostic::ErrorKind::E0021 {
+                expected: expected.clone(),
+                found: found.clone(),
+            },
+        )
+        .with_label(format!("expected `{expected}`, found `{found}`"));
+        if let Some((span, message)) = reason {
+            error = error.with_secondary(span, message);
+        }
+        self.compiler.diagnostics.emit(error);
+    }
+}
+
+impl Visit<base::Ast> for TypeChecker<'_> {
+    type Output = typed::Ast;
+
+    fn visit(&mut self, node: &base::Ast) -> Self::Output {
+        // Where each function is defined, so that a second definition can point at the first.
+        let mut definitions: BTreeMap<&str, Span> = BTreeMap::new();
+        for function in &node.functions {
+            let signature = FnSignature {
+                return_type: self.lookup_type(&function.return_type.symbol),
+                parameter_types: function
+                    .parameters
+                    .iter()
+                    .map(|dec| self.lookup_type(&dec.typ.symbol))
+                    .collect(),
+            };
+            let name = &function.name;
+            if let Some(previous) = definitions.get(name.text.as_str()) {
+                self.compiler.diagnostics.emit(
+                    diagnostic::Diagnostic::error(
+                        name.span,
+                        diagnostic::ErrorKind::E0029 {
+                            name: name.text.clone(),
+                        },
+                    )
+                    .with_label(format!("`{}` redefined here", name.text))
+                    .with_secondary(*previous, format!("previous definition of `{}`", name.text)),
+                );
+                continue;
+            }
+            definitions.insert(&name.text, name.span);
+            self.functions.insert(name.text.clone(), signature);
+        }
+
+        self.check_main(definitions.get("main").copied());
+
         let mut result = typed::Ast::default();
         for func in &node.functions {
-            result.functions.push(self.visit(func));
+            // Only the first definition of a function has its signature in `functions`,
+            // so checking the others would report errors which aren't really there.
+            if definitions[func.name.text.as_str()] == func.name.span {
+                result.functions.push(self.visit(func));
+            }
+        }
+
+        // `main` is called by whatever runs the program.
+        self.called.insert("main".to_owned());
+        for function in &node.functions {
+            let name = &function.name;
+            if self.called.contains(&name.text) || name.text.starts_with('_') {
+                continue;
+            }
+            self.allowed_lints = allowed_lints(&function.attributes);
+            let kind = diagnostic::ErrorKind::E0027 {
+                name: name.text.clone(),
+            };
+            if let Some(warning) = self.lint(diagnostic::Lint::UnusedFunction, name.span, kind) {
+                self.compiler.diagnostics.emit(warning);
+            }
         }
         result
     }
@@ -82,26 +585,60 @@ impl Visit<base::Function> for TypeChecker<'_> {
     type Output = typed::Function;
 
     fn visit(&mut self, node: &base::Function) -> Self::Output {
-        self.symbol_table.clear();
-        for param in &node.parameters {
-            let param_type_id = self.type_name_lookup(&param.typ.symbol.text);
-            self.symbol_table
-                .insert(param.name.text.clone(), param_type_id);
-        }
+        let signature = self
+            .functions
+            .get(&node.name.text)
+            .expect("Visited function without first putting it in the functions map")
+            .clone();
+        self.return_type = Some((signature.return_type, node.return_type.symbol.span));
+        self.check_attributes(&node.attributes);
+        self.allowed_lints = allowed_lints(&node.attributes);
 
-        self.visit(&node.expr);
+        // The types in the signature were already looked up while collecting every function's signature.
+        let parameters: Vec<_> = node
+            .parameters
+            .iter()
+            .zip(&signature.parameter_types)
+            .map(|(param, typ)| typed_declaration(param, *typ))
+            .collect();
+        // Parameters are in the same scope as the outermost variables of the body.
+        self.scopes.push(BTreeMap::new());
+        for param in &parameters {
+            if param.typed == Primitive::Void.id() {
+                self.compiler.diagnostics.emit(
+                    diagnostic::Diagnostic::error(
+                        param.typ.symbol.span,
+                        diagnostic::ErrorKind::E0032 {
+                            name: param.name.text.clone(),
+                        },
+                    )
+                    .with_label("parameters can't be `void`")
+                    .with_help("to declare a function without parameters, write `(void)` or `()`"),
+                );
+            }
+            if let Some(previous) = self.declare(&param.name) {
+                self.compiler.diagnostics.emit(
+                    diagnostic::Diagnostic::error(param.name.span, diagnostic::ErrorKind::E0001)
+                        .with_label("used as a parameter name again here")
+                        .with_secondary(previous.span, "first used here"),
+                );
+            }
+        }
+        let expr = self.check_block(&node.expr);
+        self.pop_scope();
+        self.check_return(node, signature.return_type);
 
         typed::Function {
             span: node.span,
-            return_type: self.visit(&node.return_type),
-            name: self.visit(&node.name),
-            parameters: self.visit(&node.parameters),
-            expr: self.visit(&node.expr),
-            signature: self
-                .functions
-                .get(&node.name.text)
-                .expect("Visited function without first putting it in the functions map")
-                .clone(),
+            attributes: node.attributes.iter().map(typed_attribute).collect(),
+            return_type: typed::Type {
+                symbol: typed_symbol(&node.return_type.symbol, signature.return_type),
+                typ: signature.return_type,
+            },
+            name: typed_symbol(&node.name, signature.return_type),
+            parameters,
+            expr,
+            signature,
         }
     }
 }
@@ -110,42 +647,166 @@ impl Visit<base::BracedExpression> for TypeChecker<'_> {
     type Output = typed::BracedExpression;
 
     fn visit(&mut self, node: &base::BracedExpression) -> Self::Output {
-        for statement in &node.statements {
-            // println!("{statement:#?}");
-            match statement {
-                base::Statement::Declaration(declaration, None) => {
-                    let dec_type_id = self.type_name_lookup(&declaration.typ.symbol.text);
-                    self.symbol_table
-                        .insert(declaration.name.text.clone(), dec_type_id);
-                }
-                base::Statement::Declaration(declaration, Some(initialization)) => {
-                    let dec_type_id = self.type_name_lookup(&declaration.typ.symbol.text);
-                    self.symbol_table
-                        .insert(declaration.name.text.clone(), dec_type_id);
-                    let initialization_type_id = self.visit(initialization).typed;
-                    if dec_type_id != initialization_type_id {
-                        diagnostic::print_error(
-                            statement.span(),
-                            diagnostic::ErrorKind::E0000,
-                            self.compiler,
-                        )
-                    }
-                }
-                base::Statement::Assignment(target, expression) => {
-                    let target_type_id = self.symbol_table.get(&target.text).copied();
-                    let expr_type_id = self.visit(expression).typed;
-                    if target_type_id != Some(expr_type_id) {
-                        diagnostic::print_error(
-                            statement.span(),
-                            diagnostic::ErrorKind::E0000,
-                            self.compiler,
-                        )
-                    }
-                }
-                base::Statement::FunctionCall(_name, _params) => todo!(),
+        self.scopes.push(BTreeMap::new());
+        let block = self.check_block(node);
+        self.pop_scope();
+        block
+    }
+}
+
+impl Visit<base::Statement> for TypeChecker<'_> {
+    type Output = typed::Statement;
+
+    fn visit(&mut self, node: &base::Statement) -> Self::Output {
+        match node {
+            base::Statement::Declaration(declaration, None) => {
+                let declaration = self.visit(declaration);
+                self.declare_local(&declaration);
+                typed::Statement::Declaration(declaration, None)
+            }
+            base::Statement::Declaration(declaration, Some(initialization)) => {
+                let mut initialization = self.visit(initialization);
+                let declaration = self.visit(declaration);
+                self.declare_local(&declaration);
+                self.type_int_literal(&mut initialization, declaration.typed);
+                self.expect_type(
+                    initialization.span(),
+                    declaration.typed,
+                    initialization.typed,
+                    Some((
+                        declaration.typ.symbol.span,
+                        "expected due to this type".to_owned(),
+                    )),
+                );
+                typed::Statement::Declaration(declaration, Some(initialization))
+            }
+            base::Statement::Assignment(target, expression) => {
+                let (target, expression) = self.check_assignment(target, expression);
+                typed::Statement::Assignment(target, expression)
+            }
+            base::Statement::CompoundAssignment(target, expression) => {
+                // The target is only read to work out what to write back to it,
+                // so like any other assignment that doesn't count as using it.
+                let used = self
+                    .lookup_variable(&target.text)
+                    .is_some_and(|variable| variable.used);
+                let (target, expression) = self.check_assignment(target, expression);
+                self.set_used(&target.text, used);
+                typed::Statement::CompoundAssignment(target, expression)
+            }
+            base::Statement::FunctionCall(call) => typed::Statement::FunctionCall(self.visit(call)),
+            base::Statement::Block(block) => typed::Statement::Block(self.visit(block)),
+            base::Statement::If(if_statement) => typed::Statement::If(self.visit(if_statement)),
+            base::Statement::While(while_loop) => typed::Statement::While(self.visit(while_loop)),
+            base::Statement::For(for_loop) => typed::Statement::For(self.visit(for_loop)),
+            base::Statement::Return(return_statement) => {
+                typed::Statement::Return(self.visit(return_statement))
+            }
+            base::Statement::Error(_) => {
+                unreachable!("Type checked a program which failed to parse")
             }
         }
-        todo!()
+    }
+}
+
+impl Visit<base::ReturnStatement> for TypeChecker<'_> {
+    type Output = typed::ReturnStatement;
+
+    fn visit(&mut self, node: &base::ReturnStatement) -> Self::Output {
+        let (return_type, return_type_span) = self
+            .return_type
+            .expect("Visited a return statement outside of a function");
+        let mut value = node.value.as_ref().map(|value| self.visit(value));
+        if let Some(value) = &mut value {
+            self.type_int_literal(value, return_type);
+        }
+        let value_type = match &value {
+            Some(value) => value.typed,
+            None => Primitive::Void.id(),
+        };
+        let span = value.as_ref().map_or(node.span, |value| value.span());
+        let reason = format!(
+            "expected `{}` because of return type",
+            type_name(return_type)
+        );
+        self.expect_type(
+            span,
+            return_type,
+            value_type,
+            Some((return_type_span, reason)),
+        );
+        typed::ReturnStatement {
+            span: node.span,
+            value,
+        }
+    }
+}
+
+impl Visit<base::IfStatement> for TypeChecker<'_> {
+    type Output = typed::IfStatement;
+
+    fn visit(&mut self, node: &base::IfStatement) -> Self::Output {
+        let condition = self.visit(&node.condition);
+        self.check_condition(&condition);
+        typed::IfStatement {
+            span: node.span,
+            condition,
+            body: self.visit(&node.body),
+            else_branch: node
+                .else_branch
+                .as_ref()
+                .map(|else_branch| match else_branch {
+                    base::ElseBranch::ElseIf(if_statement) => {
+                        typed::ElseBranch::ElseIf(Box::new(self.visit(if_statement.as_ref())))
+                    }
+                    base::ElseBranch::Else(body) => typed::ElseBranch::Else(self.visit(body)),
+                }),
+        }
+    }
+}
+
+impl Visit<base::WhileLoop> for TypeChecker<'_> {
+    type Output = typed::WhileLoop;
+
+    fn visit(&mut self, node: &base::WhileLoop) -> Self::Output {
+        let condition = self.visit(&node.condition);
+        self.check_condition(&condition);
+        typed::WhileLoop {
+            span: node.span,
+            condition,
+            body: self.visit(&node.body),
+        }
+    }
+}
+
+impl Visit<base::ForLoop> for TypeChecker<'_> {
+    type Output = typed::ForLoop;
+
+    fn visit(&mut self, node: &base::ForLoop) -> Self::Output {
+        // Variables declared in the header are only visible inside the loop.
+        self.scopes.push(BTreeMap::new());
+        let init = node
+            .init
+            .as_ref()
+            .map(|init| Box::new(self.visit(init.as_ref())));
+        let condition = node.condition.as_ref().map(|condition| {
+            let condition = self.visit(condition);
+            self.check_condition(&condition);
+            condition
+        });
+        let step = node
+            .step
+            .as_ref()
+            .map(|step| Box::new(self.visit(step.as_ref())));
+        let body = self.visit(&node.body);
+        self.pop_scope();
+        typed::ForLoop {
+            span: node.span,
+            init,
+            condition,
+            step,
+            body,
+        }
     }
 }
 
@@ -176,13 +837,266 @@ impl Visit<base::Expression> for TypeChecker<'_> {
                     inner: typed::ExpressionInner::FloatLit(visited),
                 }
             }
+            base::Expression::BoolLit(bool_lit) => {
+                let visited = self.visit(bool_lit);
+                typed::Expression {
+                    typed: visited.typed,
+                    inner: typed::ExpressionInner::BoolLit(visited),
+                }
+            }
+            base::Expression::StringLit(string_lit) => {
+                let visited = self.visit(string_lit);
+                typed::Expression {
+                    typed: visited.typed,
+                    inner: typed::ExpressionInner::StringLit(visited),
+                }
+            }
+            base::Expression::CharLit(char_lit) => {
+                let visited = self.visit(char_lit);
+                typed::Expression {
+                    typed: visited.typed,
+                    inner: typed::ExpressionInner::CharLit(visited),
+                }
+            }
             base::Expression::Symbol(symbol) => {
                 let visited = self.visit(symbol);
+                self.mark_used(&symbol.text);
                 typed::Expression {
                     typed: visited.typed,
                     inner: typed::ExpressionInner::Symbol(visited),
                 }
             }
+            base::Expression::FunctionCall(call) => {
+                let visited = self.visit(call);
+                typed::Expression {
+                    typed: visited.typed,
+                    inner: typed::ExpressionInner::FunctionCall(visited),
+                }
+            }
+            base::Expression::Binary(binary) => {
+                let visited = self.visit(binary);
+                typed::Expression {
+                    typed: visited.typed,
+                    inner: typed::ExpressionInner::Binary(visited),
+                }
+            }
+            base::Expression::Unary(unary) => {
+                let visited = self.visit(unary);
+                typed::Expression {
+                    typed: visited.typed,
+                    inner: typed::ExpressionInner::Unary(visited),
+                }
+            }
+        }
+    }
+}
+
+impl Visit<base::FunctionCall> for TypeChecker<'_> {
+    type Output = typed::FunctionCall;
+
+    fn visit(&mut self, node: &base::FunctionCall) -> Self::Output {
+        let mut arguments: Vec<typed::Expression> = node
+            .arguments
+            .iter()
+            .map(|argument| self.visit(argument))
+            .collect();
+
+        let typed = match self.functions.get(&node.name.text).cloned() {
+            Some(signature) => {
+                self.called.insert(node.name.text.clone());
+                if arguments.len() != signature.parameter_types.len() {
+                    let expected = signature.parameter_types.len();
+                    self.compiler.diagnostics.emit(
+                        diagnostic::Diagnostic::error(
+                            node.span,
+                            diagnostic::ErrorKind::E0017 {
+                                name: node.name.text.clone(),
+                                expected,
+                                found: arguments.len(),
+                            },
+                        )
+                        .with_label(format!(
+                            "expected {expected} argument{}",
+                            if expected == 1 { "" } else { "s" }
+                        ))
+                        .with_note(format!(
+                            "`{}` is declared as `{}`",
+                            node.name.text,
+                            signature_text(&node.name.text, &signature)
+                        )),
+                    );
+                } else {
+                    for (argument, parameter_type) in
+                        arguments.iter_mut().zip(&signature.parameter_types)
+                    {
+                        self.type_int_literal(argument, *parameter_type);
+                        if !types_match(*parameter_type, argument.typed) {
+                            let (expected, found) =
+                                (type_name(*parameter_type), type_name(argument.typed));
+                            self.compiler.diagnostics.emit(
+                                diagnostic::Diagnostic::error(
+                                    argument.span(),
+                                    diagnostic::ErrorKind::E0018 {
+                                        expected: expected.clone(),
+                                        found: found.clone(),
+                                    },
+                                )
+                                .with_label(format!("expected `{expected}`, found `{found}`"))
+                                .with_note(format!(
+                                    "`{}` is declared as `{}`",
+                                    node.name.text,
+                                    signature_text(&node.name.text, &signature)
+                                )),
+                            );
+                        }
+                    }
+                }
+                signature.return_type
+            }
+            None if Primitive::from_name(&node.name.text)
+                .is_some_and(|primitive| primitive.is_integer()) =>
+            {
+                self.check_conversion(node, &mut arguments)
+            }
+            None => {
+                let suggestion = diagnostic::did_you_mean(
+                    &node.name.text,
+                    self.functions.keys().map(String::as_str),
+                );
+                let mut error = diagnostic::Diagnostic::error(
+                    node.name.span,
+                    diagnostic::ErrorKind::E0016 {
+                        name: node.name.text.clone(),
+                    },
+                )
+                .with_label("not found in this program");
+                if let Some(suggestion) = suggestion {
+                    error = error.with_suggestion(typo_suggestion(
+                        "a function",
+                        node.name.span,
+                        suggestion,
+                    ));
+                }
+                self.compiler.diagnostics.emit(error);
+                ERROR_TYPE
+            }
+        };
+
+        typed::FunctionCall {
+            span: node.span,
+            name: typed_symbol(&node.name, typed),
+            arguments,
+            typed,
+        }
+    }
+}
+
+impl Visit<base::BinaryExpression> for TypeChecker<'_> {
+    type Output = typed::BinaryExpression;
+
+    fn visit(&mut self, node: &base::BinaryExpression) -> Self::Output {
+        let mut lhs = self.visit(node.lhs.as_ref());
+        let mut rhs = self.visit(node.rhs.as_ref());
+        // A literal operand takes on the type of the other operand.
+        self.type_int_literal(&mut lhs, rhs.typed);
+        self.type_int_literal(&mut rhs, lhs.typed);
+        let operands_allowed = node.op.is_logical()
+            || types_match(lhs.typed, rhs.typed) && binary_operand_allowed(node.op, lhs.typed);
+        if node.op.is_logical() {
+            self.check_condition(&lhs);
+            self.check_condition(&rhs);
+        } else if !operands_allowed {
+            let error = diagnostic::Diagnostic::error(
+                node.span,
+                diagnostic::ErrorKind::E0022 {
+                    op: node.op.to_string(),
+                    lhs: type_name(lhs.typed),
+                    rhs: type_name(rhs.typed),
+                },
+            );
+            let error = if types_match(lhs.typed, rhs.typed) {
+                error.with_note(format!(
+                    "`{}` can only be applied to {}",
+                    node.op,
+                    binary_operand_description(node.op)
+                ))
+            } else {
+                error
+                    .with_secondary(lhs.span(), format!("`{}`", type_name(lhs.typed)))
+                    .with_secondary(rhs.span(), format!("`{}`", type_name(rhs.typed)))
+                    .with_note("both operands must have the same type")
+            };
+            self.compiler.diagnostics.emit(error);
+        }
+
+        let typed = if node.op.is_comparison() || node.op.is_logical() {
+            Primitive::Bool.id()
+        } else if !operands_allowed {
+            // Don't guess which operand had the intended type, so the error isn't reported again.
+            ERROR_TYPE
+        } else {
+            lhs.typed
+        };
+
+        typed::BinaryExpression {
+            span: node.span,
+            op: node.op,
+            lhs: Box::new(lhs),
+            rhs: Box::new(rhs),
+            typed,
+        }
+    }
+}
+
+impl Visit<base::UnaryExpression> for TypeChecker<'_> {
+    type Output = typed::UnaryExpression;
+
+    fn visit(&mut self, node: &base::UnaryExpression) -> Self::Output {
+        let operand = self.visit(node.operand.as_ref());
+        let typed = match node.op {
+            base::UnaryOp::Not if types_match(Primitive::Bool.id(), operand.typed) => {
+                Primitive::Bool.id()
+            }
+            base::UnaryOp::Not => {
+                self.check_condition(&operand);
+                ERROR_TYPE
+            }
+            base::UnaryOp::Neg | base::UnaryOp::BitNot => {
+                let allowed = match Primitive::from_id(operand.typed) {
+                    Some(primitive) if node.op == base::UnaryOp::Neg => primitive.is_numeric(),
+                    Some(primitive) => primitive.is_integer(),
+                    // Only the error type isn't primitive, and its error has been reported already.
+                    None => true,
+                };
+                if allowed {
+                    operand.typed
+                } else {
+                    let operands = if node.op == base::UnaryOp::Neg {
+                        "numbers"
+                    } else {
+                        "integers"
+                    };
+                    self.compiler.diagnostics.emit(
+                        diagnostic::Diagnostic::error(
+                            node.span,
+                            diagnostic::ErrorKind::E0023 {
+                                op: node.op.to_string(),
+                                operand: type_name(operand.typed),
+                            },
+                        )
+                        .with_note(format!("`{}` can only be applied to {operands}", node.op)),
+                    );
+                    // Like a binary expression, so the enclosing expression doesn't report it again.
+                    ERROR_TYPE
+                }
+            }
+        };
+
+        typed::UnaryExpression {
+            span: node.span,
+            op: node.op,
+            operand: Box::new(operand),
+            typed,
         }
     }
 }
@@ -191,7 +1105,11 @@ impl Visit<base::IntLit> for TypeChecker<'_> {
     type Output = typed::IntLit;
 
     fn visit(&mut self, node: &base::IntLit) -> Self::Output {
-        todo!()
+        typed::IntLit {
+            span: node.span,
+            value: node.value,
+            typed: Primitive::Int.id(),
+        }
     }
 }
 
@@ -199,7 +1117,47 @@ impl Visit<base::FloatLit> for TypeChecker<'_> {
     type Output = typed::FloatLit;
 
     fn visit(&mut self, node: &base::FloatLit) -> Self::Output {
-        todo!()
+        typed::FloatLit {
+            span: node.span,
+            value: node.value,
+            typed: Primitive::Float.id(),
+        }
+    }
+}
+
+impl Visit<base::BoolLit> for TypeChecker<'_> {
+    type Output = typed::BoolLit;
+
+    fn visit(&mut self, node: &base::BoolLit) -> Self::Output {
+        typed::BoolLit {
+            span: node.span,
+            value: node.value,
+            typed: Primitive::Bool.id(),
+        }
+    }
+}
+
+impl Visit<base::StringLit> for TypeChecker<'_> {
+    type Output = typed::StringLit;
+
+    fn visit(&mut self, node: &base::StringLit) -> Self::Output {
+        typed::StringLit {
+            span: node.span,
+            value: node.value.clone(),
+            typed: Primitive::String.id(),
+        }
+    }
+}
+
+impl Visit<base::CharLit> for TypeChecker<'_> {
+    type Output = typed::CharLit;
+
+    fn visit(&mut self, node: &base::CharLit) -> Self::Output {
+        typed::CharLit {
+            span: node.span,
+            value: node.value,
+            typed: Primitive::Char.id(),
+        }
     }
 }
 
@@ -207,7 +1165,31 @@ impl Visit<base::Symbol> for TypeChecker<'_> {
     type Output = typed::Symbol;
 
     fn visit(&mut self, node: &base::Symbol) -> Self::Output {
-        todo!()
+        let typed = match self.lookup_variable(&node.text) {
+            Some(variable) => variable.typed,
+            None => {
+                let suggestion = diagnostic::did_you_mean(
+                    &node.text,
+                    self.scopes
+                        .iter()
+                        .flat_map(|scope| scope.keys().map(String::as_str)),
+                );
+                let mut error = diagnostic::Diagnostic::error(
+                    node.span,
+                    diagnostic::ErrorKind::E0015 {
+                        name: node.text.clone(),
+                    },
+                )
+                .with_label("not found in this scope");
+                if let Some(suggestion) = suggestion {
+                    error =
+                        error.with_suggestion(typo_suggestion("a variable", node.span, suggestion));
+                }
+                self.compiler.diagnostics.emit(error);
+                ERROR_TYPE
+            }
+        };
+        typed_symbol(node, typed)
     }
 }
 
@@ -215,15 +1197,218 @@ impl Visit<base::Type> for TypeChecker<'_> {
     type Output = typed::Type;
 
     fn visit(&mut self, node: &base::Type) -> Self::Output {
-        todo!()
+        let typ = self.lookup_type(&node.symbol);
+        typed::Type {
+            symbol: typed_symbol(&node.symbol, typ),
+            typ,
+        }
     }
 }
 
-impl Visit<Vec<base::Declaration>> for TypeChecker<'_> {
-    type Output = Vec<typed::Declaration>;
+impl Visit<base::Declaration> for TypeChecker<'_> {
+    type Output = typed::Declaration;
 
-    fn visit(&mut self, node: &Vec<base::Declaration>) -> Self::Output {
-        todo!()
+    fn visit(&mut self, node: &base::Declaration) -> Self::Output {
+        let typ = self.visit(&node.typ);
+        typed::Declaration {
+            span: node.span,
+            name: typed_symbol(&node.name, typ.typ),
+            typed: typ.typ,
+            typ,
+        }
+    }
+}
+
+/// The type of anything whose type couldn't be worked out because of an error.
+/// The error has already been reported, so this type is compatible with every other type
+/// to keep that one mistake from being reported over and over.
+const ERROR_TYPE: TypeID = Primitive::FIRST_FREE_ID;
+
+/// Whether a value of type `found` can be used where a value of type `expected` is needed.
+fn types_match(expected: TypeID, found: TypeID) -> bool {
+    expected == found || expected == ERROR_TYPE || found == ERROR_TYPE
+}
+
+fn type_name(typ: TypeID) -> String {
+    match Primitive::from_id(typ) {
+        Some(primitive) => primitive.name().to_owned(),
+        None => "{unknown}".to_owned(),
+    }
+}
+
+/// How a function's signature would be written in a program, like `int add(int, int)`.
+fn signature_text(name: &str, signature: &FnSignature) -> String {
+    let parameters: Vec<String> = signature
+        .parameter_types
+        .iter()
+        .map(|typ| type_name(*typ))
+        .collect();
+    format!(
+        "{} {name}({})",
+        type_name(signature.return_type),
+        parameters.join(", ")
+    )
+}
+
+/// Replaces a misspelled name with the one it was probably meant to be.
+/// `kind` is what the name refers to, like `a variable`.
+/// The similar name could just as well be a different variable that happens to be in scope,
+/// so this is never applied automatically.
+fn typo_suggestion(kind: &str, span: Span, suggestion: &str) -> diagnostic::Suggestion {
+    diagnostic::Suggestion::new(
+        format!("{kind} with a similar name exists: `{suggestion}`"),
+        diagnostic::Applicability::MaybeIncorrect,
+    )
+    .replace(span, suggestion)
+}
+
+/// Whether `op` can be applied to two operands of type `typ`.
+fn binary_operand_allowed(op: base::BinaryOp, typ: TypeID) -> bool {
+    use base::BinaryOp::*;
+    let Some(primitive) = Primitive::from_id(typ) else {
+        // Only the error type isn't primitive, and its error has been reported already.
+        return true;
+    };
+    match op {
+        Equals | NotEquals => true,
+        Less | LessEquals | Greater | GreaterEquals => {
+            primitive.is_numeric() || primitive == Primitive::Char
+        }
+        Add | Sub | Mul | Div | Rem => primitive.is_numeric(),
+        BitAnd | BitOr | BitXor | ShiftLeft | ShiftRight => primitive.is_integer(),
+        And | Or => primitive == Primitive::Bool,
+    }
+}
+
+/// The kinds of operands `binary_operand_allowed` accepts for `op`, for error messages.
+fn binary_operand_description(op: base::BinaryOp) -> &'static str {
+    use base::BinaryOp::*;
+    match op {
+        Equals | NotEquals => "operands of any type",
+        Less | LessEquals | Greater | GreaterEquals => "numbers and `char`s",
+        Add | Sub | Mul | Div | Rem => "numbers",
+        BitAnd | BitOr | BitXor | ShiftLeft | ShiftRight => "integers",
+        And | Or => "`bool`s",
+    }
+}
+
+/// The location of the char starting at byte `char_num` of `code`.
+fn location_at(code: &str, char_num: usize) -> Location {
+    let before = &code[..char_num];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    Location {
+        line: before.matches('\n').count() + 1,
+        col: before[line_start..].chars().count() + 1,
+        char_num,
+    }
+}
+
+/// The location of the last char of `code`, where anything missing from the end of the program would go.
+fn end_location(code: &str) -> Location {
+    let mut end = Location {
+        line: 1,
+        col: 1,
+        char_num: 0,
+    };
+    let (mut line, mut col) = (1, 1);
+    for (char_num, c) in code.char_indices() {
+        end = Location {
+            line,
+            col,
+            char_num,
+        };
+        if c == '\n' {
+            line += 1;
+            col = 1;
+        } else {
+            col += 1;
+        }
+    }
+    end
+}
+
+/// A value of type `typ` to suggest returning, for types which have an obvious one.
+fn placeholder_value(typ: TypeID) -> Option<&'static str> {
+    match Primitive::from_id(typ)? {
+        Primitive::Bool => Some("false"),
+        primitive if primitive.is_integer() => Some("0"),
+        Primitive::Float => Some("0.0"),
+        Primitive::String => Some("\"\""),
+        _ => None,
+    }
+}
+
+/// Whether running `statement` never gets to the statement after it,
+/// because it always ends in a `return` or loops forever.
+fn never_finishes(statement: &base::Statement) -> bool {
+    match statement {
+        base::Statement::Return(_) => true,
+        base::Statement::Block(block) => block.statements.iter().any(never_finishes),
+        base::Statement::If(if_statement) => if_never_finishes(if_statement),
+        // There's no `break`, so a loop whose condition is always true can only be left by returning.
+        base::Statement::While(while_loop) => is_true(&while_loop.condition),
+        base::Statement::For(for_loop) => for_loop.condition.as_ref().is_none_or(is_true),
+        _ => false,
+    }
+}
+
+/// An `if` never finishes if it has an `else` and no branch finishes.
+fn if_never_finishes(node: &base::IfStatement) -> bool {
+    let else_finishes = match &node.else_branch {
+        Some(base::ElseBranch::ElseIf(if_statement)) => !if_never_finishes(if_statement),
+        Some(base::ElseBranch::Else(body)) => !body.statements.iter().any(never_finishes),
+        None => true,
+    };
+    !else_finishes && node.body.statements.iter().any(never_finishes)
+}
+
+fn is_true(expr: &base::Expression) -> bool {
+    matches!(
+        expr,
+        base::Expression::BoolLit(base::BoolLit { value: true, .. })
+    )
+}
+
+/// The lints named by the `#[allow(...)]` attributes in `attributes`.
+fn allowed_lints(attributes: &[base::Attribute]) -> Vec<diagnostic::Lint> {
+    attributes
+        .iter()
+        .filter(|attribute| attribute.name.text == "allow")
+        .flat_map(|attribute| &attribute.arguments)
+        .filter_map(|argument| diagnostic::Lint::from_name(&argument.text))
+        .collect()
+}
+
+fn typed_attribute(attribute: &base::Attribute) -> typed::Attribute {
+    let void = Primitive::Void.id();
+    typed::Attribute {
+        span: attribute.span,
+        name: typed_symbol(&attribute.name, void),
+        arguments: attribute
+            .arguments
+            .iter()
+            .map(|argument| typed_symbol(argument, void))
+            .collect(),
+    }
+}
+
+fn typed_declaration(declaration: &base::Declaration, typed: TypeID) -> typed::Declaration {
+    typed::Declaration {
+        span: declaration.span,
+        typ: typed::Type {
+            symbol: typed_symbol(&declaration.typ.symbol, typed),
+            typ: typed,
+        },
+        name: typed_symbol(&declaration.name, typed),
+        typed,
+    }
+}
+
+fn typed_symbol(symbol: &base::Symbol, typed: TypeID) -> typed::Symbol {
+    typed::Symbol {
+        text: symbol.text.clone(),
+        span: symbol.span,
+        typed,
     }
 }
 
@@ -232,3 +1417,317 @@ trait Visit<Node> {
 
     fn visit(&mut self, node: &Node) -> Self::Output;
 }
+
+#[cfg(test)]
+fn check_str(code: &str, lints: diagnostic::LintLevels) -> (typed::Ast, Compiler) {
+    let compiler = Compiler {
+        config: rowdy_compiler::Config {
+            source: rowdy_location::Source::Anonymous,
+            lints,
+            error_format: rowdy_compiler::ErrorFormat::Human,
+            color: rowdy_compiler::ColorChoice::Never,
+        },
+        code: code.to_owned(),
+        diagnostics: diagnostic::DiagnosticSink::new(),
+    };
+    let ast = rowdy_parser::parse_tokens(rowdy_lexer::tokenize(&compiler), &compiler).unwrap();
+    (type_check(&ast, &compiler), compiler)
+}
+
+/// The codes of the diagnostics emitted while checking `code`, with every lint allowed.
+#[cfg(test)]
+fn diagnostic_codes(code: &str) -> Vec<&'static str> {
+    lint_codes(
+        code,
+        diagnostic::LintLevels::all(diagnostic::LintLevel::Allow),
+    )
+}
+
+#[cfg(test)]
+fn lint_codes(code: &str, lints: diagnostic::LintLevels) -> Vec<&'static str> {
+    let (_, compiler) = check_str(code, lints);
+    let diagnostics = compiler.diagnostics.diagnostics();
+    diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.error_kind().code())
+        .collect()
+}
+
+#[test]
+fn every_expression_is_typed() {
+    let (ast, compiler) = check_str(
+        "float f(float x) { bool b = x * 2.0 < 1.5 || !true; return -x; } int main() { return 0; }",
+        diagnostic::LintLevels::default(),
+    );
+    assert!(!compiler.diagnostics.has_errors());
+    let function = &ast.functions[0];
+    assert_eq!(function.signature.return_type, Primitive::Float.id());
+
+    let typed::Statement::Declaration(declaration, Some(init)) = &function.expr.statements[0]
+    else {
+        panic!("expected a declaration");
+    };
+    assert_eq!(declaration.typed, Primitive::Bool.id());
+    let typed::ExpressionInner::Binary(or) = &init.inner else {
+        panic!("expected `||`");
+    };
+    let typed::ExpressionInner::Binary(less) = &or.lhs.inner else {
+        panic!("expected `<`");
+    };
+    assert_eq!(less.typed, Primitive::Bool.id());
+    assert_eq!(less.lhs.typed, Primitive::Float.id());
+    assert_eq!(or.rhs.typed, Primitive::Bool.id());
+}
+
+#[test]
+fn type_errors_are_reported() {
+    assert_eq!(diagnostic_codes("int main() { return 1.0; }"), ["E0021"]);
+    assert_eq!(
+        diagnostic_codes("int main() { if (1) { } return 0; }"),
+        ["E0021"]
+    );
+    assert_eq!(
+        diagnostic_codes("int main() { float x = 1.0 & 2.0; return 0; }"),
+        ["E0022"]
+    );
+    assert_eq!(
+        diagnostic_codes("int main() { foo x; return 0; }"),
+        ["E0014"]
+    );
+    assert_eq!(
+        diagnostic_codes("int main() { x = 1; return 0; }"),
+        ["E0015"]
+    );
+    assert_eq!(diagnostic_codes("int main() { return f(); }"), ["E0016"]);
+    assert_eq!(
+        diagnostic_codes("int f(int a) { return a; } int main() { return f(1, 2); }"),
+        ["E0017"]
+    );
+    assert_eq!(
+        diagnostic_codes("int f(int a) { return a; } int main() { return f(true); }"),
+        ["E0018"]
+    );
+    assert_eq!(
+        diagnostic_codes("int main() { return 1.0 + true; }"),
+        ["E0022"]
+    );
+    assert_eq!(
+        diagnostic_codes("int main() { return -true + 1; }"),
+        ["E0023"]
+    );
+    assert_eq!(
+        diagnostic_codes("int main() { int x = !1; return x; }"),
+        ["E0021"]
+    );
+    assert_eq!(
+        diagnostic_codes("int f(void a) { return 0; } int main() { return 0; }"),
+        ["E0032"]
+    );
+    assert!(diagnostic_codes("int main() { return 0; }").is_empty());
+}
+
+#[test]
+fn sized_integers_are_checked() {
+    assert!(diagnostic_codes(
+        "i64 f(u8 a) { i64 b = -3; b = i64(a) * b + 1; return b; } \
+         int main() { i8 x = 3; x += -1; u16 y = 65535; if (y > 0) { } return int(f(200)); }"
+    )
+    .is_empty());
+    assert_eq!(
+        diagnostic_codes("int main() { i8 x = 3; int y = x; return y; }"),
+        ["E0021"]
+    );
+    assert_eq!(
+        diagnostic_codes("int main() { i8 x = 3; u8 y = 3; return int(x + y); }"),
+        ["E0022"]
+    );
+    assert_eq!(
+        diagnostic_codes("int main() { i8 x = 128; return 0; }"),
+        ["E0034"]
+    );
+    assert_eq!(
+        diagnostic_codes("int main() { u32 x = -1; return 0; }"),
+        ["E0034"]
+    );
+    assert_eq!(
+        diagnostic_codes("int main() { return int(1.5); }"),
+        ["E0018"]
+    );
+    assert_eq!(
+        diagnostic_codes("int main() { return int(1, 2); }"),
+        ["E0017"]
+    );
+}
+
+#[test]
+fn blocks_have_their_own_scope() {
+    assert_eq!(
+        diagnostic_codes("int main() { if (true) { int x = 1; } return x; }"),
+        ["E0015"]
+    );
+    assert_eq!(
+        diagnostic_codes("int main() { { int x = 1; } return x; }"),
+        ["E0015"]
+    );
+    assert_eq!(
+        diagnostic_codes("int main() { for (int i = 0; i < 3; i = i + 1) { } return i; }"),
+        ["E0015"]
+    );
+    assert_eq!(
+        diagnostic_codes("int main() { int x = 1; int x = 2; return x; }"),
+        ["E0019"]
+    );
+    assert_eq!(
+        diagnostic_codes("int f(int a) { int a = 1; return a; } int main() { return f(1); }"),
+        ["E0019"]
+    );
+
+    for shadowing in [
+        "int main() { int x = 1; while (false) { float x = 2.0; } return x; }",
+        "int main() { int x = 1; { { float x = 2.0; } } return x; }",
+    ] {
+        assert!(diagnostic_codes(shadowing).is_empty());
+        let mut lints = diagnostic::LintLevels::all(diagnostic::LintLevel::Allow);
+        lints.set(diagnostic::Lint::Shadowing, diagnostic::LintLevel::Warn);
+        let (_, compiler) = check_str(shadowing, lints);
+        assert!(!compiler.diagnostics.has_errors());
+        assert_eq!(compiler.diagnostics.warning_count(), 1);
+    }
+}
+
+#[test]
+fn functions_return_a_value() {
+    assert_eq!(
+        diagnostic_codes("int f(int x) { if (x > 0) { return 1; } } int main() { return f(1); }"),
+        ["E0033"]
+    );
+    assert!(diagnostic_codes("int main() { int x = 1; }").is_empty());
+    assert!(
+        diagnostic_codes("int f() { while (true) { return 1; } } int main() { return f(); }")
+            .is_empty()
+    );
+    assert!(diagnostic_codes("int f() { for (;;) { } } int main() { return f(); }").is_empty());
+    assert!(diagnostic_codes(
+        "int f(bool b) { if (b) { return 1; } else { return 2; } } int main() { return f(true); }"
+    )
+    .is_empty());
+    assert!(diagnostic_codes("void f() { } int main() { f(); return 0; }").is_empty());
+}
+
+#[test]
+fn missing_returns_are_suggested() {
+    let suggested = |code: &str| {
+        let (_, compiler) = check_str(
+            code,
+            diagnostic::LintLevels::all(diagnostic::LintLevel::Allow),
+        );
+        let diagnostics = compiler.diagnostics.take();
+        let [edit] = diagnostics[0].suggestions()[0].edits.as_slice() else {
+            panic!("expected a suggestion with one edit")
+        };
+        let mut fixed = code.to_owned();
+        fixed.replace_range(edit.byte_range(code), edit.text());
+        fixed
+    };
+    assert_eq!(
+        suggested("int f() {\n    int x = 1;\n}\nint main() { return f(); }"),
+        "int f() {\n    int x = 1;\n    return 0;\n}\nint main() { return f(); }"
+    );
+    assert_eq!(
+        suggested("bool f() { if (true) { return true; } } int main() { return 0; }"),
+        "bool f() { if (true) { return true; } return false; } int main() { return 0; }"
+    );
+}
+
+#[test]
+fn typo_suggestions_are_not_applied() {
+    // `yy` could be a typo for `y`, but replacing it would also compile if `x + x` was meant.
+    let (_, compiler) = check_str(
+        "int main() { int x = 1; int y = 2; return x + yy; }",
+        diagnostic::LintLevels::all(diagnostic::LintLevel::Allow),
+    );
+    let diagnostics = compiler.diagnostics.take();
+    let suggestions: Vec<_> = diagnostics
+        .iter()
+        .flat_map(diagnostic::Diagnostic::suggestions)
+        .collect();
+    assert_eq!(suggestions.len(), 1);
+    assert_eq!(
+        diagnostic::apply_suggestions(&compiler.code, suggestions).1,
+        0
+    );
+}
+
+#[test]
+fn lints_are_reported() {
+    let lint_codes = |code| lint_codes(code, diagnostic::LintLevels::default());
+    assert_eq!(
+        lint_codes("int main() { int x = 1; int _y = 2; x = 3; return 0; }"),
+        ["E0026"]
+    );
+    assert_eq!(
+        lint_codes("int main() { int x = 0; x += 1; return 0; }"),
+        ["E0026"]
+    );
+    assert!(lint_codes("int main() { int x = 0; x += 1; return x; }").is_empty());
+    assert_eq!(
+        lint_codes("int f(int a) { return a; } int _g() { return 0; } int main() { return 0; }"),
+        ["E0027"]
+    );
+    assert_eq!(
+        lint_codes("int main() { if (true) { return 1; } else { return 2; } return 3; }"),
+        ["E0028"]
+    );
+    assert!(lint_codes("int main() { if (true) { return 1; } return 2; }").is_empty());
+    assert_eq!(
+        lint_codes("int main() { while (true) { } return 1; }"),
+        ["E0028"]
+    );
+    assert!(lint_codes(
+        "#[allow(unused_variable, unused_function)] int f(int a) { return 0; } int main() { return 0; }"
+    )
+    .is_empty());
+    assert_eq!(
+        lint_codes("#[alow(unused_variable)] int main() { return 0; }"),
+        ["E0024"]
+    );
+    assert_eq!(
+        lint_codes("#[allow(unused_variables)] int main() { return 0; }"),
+        ["E0025"]
+    );
+
+    let mut lints = diagnostic::LintLevels::default();
+    lints.set(
+        diagnostic::Lint::UnusedFunction,
+        diagnostic::LintLevel::Allow,
+    );
+    lints.warnings_are_errors = true;
+    let (_, compiler) = check_str(
+        "int f() { int x = 1; return 0; } int main() { return 0; }",
+        lints,
+    );
+    assert_eq!(compiler.diagnostics.error_count(), 1);
+    assert_eq!(compiler.diagnostics.warning_count(), 0);
+}
+
+#[test]
+fn functions_are_defined_once() {
+    assert_eq!(
+        diagnostic_codes(
+            "int f() { return 1; } int f(int a) { return a; } int main() { return f(); }"
+        ),
+        ["E0029"]
+    );
+    assert_eq!(
+        diagnostic_codes("int main() { return 0; } int main() { return 1; }"),
+        ["E0029"]
+    );
+}
+
+#[test]
+fn main_is_checked() {
+    assert_eq!(diagnostic_codes(""), ["E0030"]);
+    assert_eq!(diagnostic_codes("int f() { return 0; }"), ["E0030"]);
+    assert_eq!(diagnostic_codes("float main() { return 1.0; }"), ["E0031"]);
+    assert_eq!(diagnostic_codes("int main(int a) { return a; }"), ["E0031"]);
+}
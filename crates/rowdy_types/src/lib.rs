@@ -5,3 +5,141 @@ pub struct FnSignature {
     pub return_type: TypeID,
     pub parameter_types: Vec<TypeID>,
 }
+
+/// The types which are built into the language.
+/// Each one always has the same `TypeID`, so they can be referred to without looking them up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Primitive {
+    Void,
+    Bool,
+    Char,
+    Int,
+    Float,
+    String,
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+}
+
+impl Primitive {
+    /// Every primitive type, ordered by `TypeID`.
+    pub const ALL: [Primitive; 14] = [
+        Self::Void,
+        Self::Bool,
+        Self::Char,
+        Self::Int,
+        Self::Float,
+        Self::String,
+        Self::I8,
+        Self::I16,
+        Self::I32,
+        Self::I64,
+        Self::U8,
+        Self::U16,
+        Self::U32,
+        Self::U64,
+    ];
+
+    /// The first `TypeID` which doesn't belong to a primitive type.
+    pub const FIRST_FREE_ID: TypeID = Self::ALL.len() as TypeID;
+
+    pub fn id(self) -> TypeID {
+        self as TypeID
+    }
+
+    pub fn from_id(id: TypeID) -> Option<Self> {
+        Self::ALL.get(id as usize).copied()
+    }
+
+    /// The name used to refer to this type in a program.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Void => "void",
+            Self::Bool => "bool",
+            Self::Char => "char",
+            Self::Int => "int",
+            Self::Float => "float",
+            Self::String => "string",
+            Self::I8 => "i8",
+            Self::I16 => "i16",
+            Self::I32 => "i32",
+            Self::I64 => "i64",
+            Self::U8 => "u8",
+            Self::U16 => "u16",
+            Self::U32 => "u32",
+            Self::U64 => "u64",
+        }
+    }
+
+    pub fn is_integer(self) -> bool {
+        self.bits().is_some()
+    }
+
+    /// How many bits wide an integer type is, or `None` for types which aren't integers.
+    /// `int` is 32 bits.
+    pub fn bits(self) -> Option<u32> {
+        Some(match self {
+            Self::I8 | Self::U8 => 8,
+            Self::I16 | Self::U16 => 16,
+            Self::Int | Self::I32 | Self::U32 => 32,
+            Self::I64 | Self::U64 => 64,
+            _ => return None,
+        })
+    }
+
+    /// Whether an integer type can hold negative values.
+    pub fn is_signed(self) -> bool {
+        matches!(
+            self,
+            Self::Int | Self::I8 | Self::I16 | Self::I32 | Self::I64
+        )
+    }
+
+    /// The smallest and largest values of an integer type, or `None` for types which aren't integers.
+    pub fn integer_range(self) -> Option<(i128, i128)> {
+        let bits = self.bits()?;
+        Some(if self.is_signed() {
+            (-(1 << (bits - 1)), (1 << (bits - 1)) - 1)
+        } else {
+            (0, (1 << bits) - 1)
+        })
+    }
+
+    /// Whether arithmetic can be done on this type.
+    pub fn is_numeric(self) -> bool {
+        self.is_integer() || self == Self::Float
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|primitive| primitive.name() == name)
+    }
+}
+
+#[test]
+fn primitive_ids_match_their_order() {
+    for (id, primitive) in Primitive::ALL.into_iter().enumerate() {
+        assert_eq!(primitive.id(), id as TypeID);
+        assert_eq!(Primitive::from_id(id as TypeID), Some(primitive));
+        assert_eq!(Primitive::from_name(primitive.name()), Some(primitive));
+    }
+    assert_eq!(Primitive::from_id(Primitive::FIRST_FREE_ID), None);
+}
+
+#[test]
+fn integer_ranges() {
+    assert_eq!(Primitive::I8.integer_range(), Some((-128, 127)));
+    assert_eq!(Primitive::U16.integer_range(), Some((0, 65535)));
+    assert_eq!(
+        Primitive::Int.integer_range(),
+        Some((i32::MIN.into(), i32::MAX.into()))
+    );
+    assert_eq!(Primitive::U64.integer_range(), Some((0, u64::MAX.into())));
+    assert_eq!(Primitive::Float.integer_range(), None);
+}
@@ -1,14 +1,18 @@
-use rowdy_compiler::Config;
+use rowdy_compiler::Command;
 use std::process;
 
 fn main() {
-    let config = match Config::new_from_args() {
+    let command = match Command::new_from_args() {
         Ok(val) => val,
         Err(e) => {
-            println!("Error: {}", e);
-            process::exit(0);
+            eprintln!("error: {e}");
+            process::exit(1);
         }
     };
 
-    rowdy_driver::run(config);
+    process::exit(match command {
+        Command::Run(config) => rowdy_driver::run(config),
+        Command::Explain(code) => rowdy_driver::explain(&code),
+        Command::Fix(config) => rowdy_driver::fix(config),
+    });
 }